@@ -6,6 +6,7 @@ use time::format_description::FormatItem::*;
 use time::format_description::*;
 use time::macros::format_description as fd;
 use time::*;
+use time_tests::formats::*;
 
 fn bench_time_parsing(c: &mut Criterion) {
     let mut group = c.benchmark_group("Baseline");
@@ -79,64 +80,3 @@ fn bench_time_parsing(c: &mut Criterion) {
 
 criterion_group!(benches, bench_time_parsing);
 criterion_main!(benches);
-
-const YEAR: FormatItem = Component(Year({
-    let mut value = modifier::Year::default();
-    value.padding = modifier::Padding::Zero;
-    value.repr = modifier::YearRepr::Full;
-    value.iso_week_based = false;
-    value.sign_is_mandatory = false;
-    value
-}));
-
-const MONTH: FormatItem = Component(Month({
-    let mut value = modifier::Month::default();
-    value.padding = modifier::Padding::Zero;
-    value.repr = modifier::MonthRepr::Numerical;
-    value.case_sensitive = true;
-    value
-}));
-
-const DAY: FormatItem = Component(Day({
-    let mut value = modifier::Day::default();
-    value.padding = modifier::Padding::Zero;
-    value
-}));
-
-const HOUR: FormatItem = Component(Hour({
-    let mut value = modifier::Hour::default();
-    value.padding = modifier::Padding::Zero;
-    value.is_12_hour_clock = false;
-    value
-}));
-
-const MINUTE: FormatItem = Component(Minute({
-    let mut value = modifier::Minute::default();
-    value.padding = modifier::Padding::Zero;
-    value
-}));
-
-const SECOND: FormatItem = Component(Second({
-    let mut value = modifier::Second::default();
-    value.padding = modifier::Padding::Zero;
-    value
-}));
-
-const SUBSECOND: FormatItem = Component(Subsecond({
-    let mut value = modifier::Subsecond::default();
-    value.digits = modifier::SubsecondDigits::OneOrMore;
-    value
-}));
-
-const OFFSET_HOUR: FormatItem = Component(OffsetHour({
-    let mut value = modifier::OffsetHour::default();
-    value.sign_is_mandatory = true;
-    value.padding = modifier::Padding::Zero;
-    value
-}));
-
-const OFFSET_MINUTE: FormatItem = Component(OffsetMinute({
-    let mut value = modifier::OffsetMinute::default();
-    value.padding = modifier::Padding::Zero;
-    value
-}));
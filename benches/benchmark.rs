@@ -1,79 +1,135 @@
 #![allow(unused)]
-use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+#[macro_use]
+mod common;
+
+use common::Expected::{Decodes, Rejected};
+use criterion::{criterion_group, criterion_main, Criterion};
+use time::macros::datetime;
 use time_tests::*;
 
 fn bench_time_parsing(c: &mut Criterion) {
     let mut group = c.benchmark_group("OffsetDateTime");
-    for i in [
-        "2016-03-07T22:36:55.135+03:30",
-        "2013-09-17 23:59-01:00",
-        "2015-11-19 01:01:39+01:00",
-        "2014-10-18 00:00:38.697+00:00",
-        "2017-04-11T14:35+02:00",
-    ]
-    .iter()
-    {
-        group.bench_with_input(BenchmarkId::new("1st ITER", i), i, |b, i| {
-            b.iter(|| first::odt_iteration(*i))
-        });
-        group.bench_with_input(BenchmarkId::new("2nd ITER", i), i, |b, i| {
-            b.iter(|| second::odt_iteration(*i))
-        });
-        group.bench_with_input(BenchmarkId::new("3rd ITER", i), i, |b, i| {
-            b.iter(|| third::odt_iteration(*i))
-        });
-        group.bench_with_input(BenchmarkId::new("4th ITER", i), i, |b, i| {
-            b.iter(|| fourth::odt_iteration(*i))
-        });
+    for (i, expected) in common::OFFSET_DATE_TIMES {
+        bench_strategies!(&mut group, i, Decodes(expected), parse_offset_date_time);
     }
     group.finish();
 
     let mut group = c.benchmark_group("PrimitiveDateTime");
-    for i in [
-        "2018-12-01 04:09:19.543",
-        "2017-11-30 03:08",
-        "2011-05-24 21:02Z",
-        "2019-01-02 05:10:20",
-        "2013-07-26 23:04:14Z",
-        "2012-06-25 22:03:13.321Z",
-        "2014-08-27T00:05",
-        "2008-02-21T18:59Z",
-        "2016-10-29T02:07:17",
-        "2010-04-23T20:01:11Z",
-        "2015-09-28T01:06:16.432",
-        "2009-03-22T19:00:10.21Z",
-    ]
-    .iter()
-    {
-        group.bench_with_input(BenchmarkId::new("1st ITER", i), i, |b, i| {
-            b.iter(|| first::pdt_iteration(*i))
-        });
-        group.bench_with_input(BenchmarkId::new("2nd ITER", i), i, |b, i| {
-            b.iter(|| second::pdt_iteration(*i))
-        });
-        group.bench_with_input(BenchmarkId::new("3rd ITER", i), i, |b, i| {
-            b.iter(|| third::pdt_iteration(*i))
-        });
-        group.bench_with_input(BenchmarkId::new("4th ITER", i), i, |b, i| {
-            b.iter(|| fourth::pdt_iteration(*i))
-        });
+    for (i, expected) in common::PRIMITIVE_DATE_TIMES {
+        bench_strategies!(&mut group, i, Decodes(expected), parse_primitive_date_time);
     }
     group.finish();
 
     let mut group = c.benchmark_group("Time");
-    for i in ["20:45:31.133", "21:46:32", "19:44"].iter() {
-        group.bench_with_input(BenchmarkId::new("1st ITER", i), i, |b, i| {
-            b.iter(|| first::time_iteration(*i))
-        });
-        group.bench_with_input(BenchmarkId::new("2nd ITER", i), i, |b, i| {
-            b.iter(|| second::time_iteration(*i))
-        });
-        group.bench_with_input(BenchmarkId::new("3rd ITER", i), i, |b, i| {
-            b.iter(|| third::time_iteration(*i))
-        });
+    for (i, expected) in common::TIMES {
+        bench_strategies!(&mut group, i, Decodes(expected), parse_time);
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("Date");
+    for (i, expected) in common::DATES {
+        bench_strategies!(&mut group, i, Decodes(expected), parse_date);
+    }
+    group.finish();
+}
+
+const GARBAGE: [&str; 4] = ["", "not a date", "0000000000000000000000000000", "🦀🦀🦀"];
+
+const REJECTED_OFFSET_DATE_TIMES: [&str; 4] = [
+    "2016-03-07 22:36:55.135",
+    "2016-13-07 22:36:55.135+03:30",
+    "2016-03-07T22:36:55.135+03:30x",
+    "2016-03-07T22:3",
+];
+
+const REJECTED_PRIMITIVE_DATE_TIMES: [&str; 4] = [
+    "2018-12-01 04:09:19.543+01:00",
+    "2018-12-32 04:09:19.543",
+    "2018-12-01 04:09:19.543x",
+    "2018-12-01 04",
+];
+
+const REJECTED_TIMES: [&str; 4] = ["20:45:31.133Z", "24:45:31.133", "20:45:31x", "20"];
+
+const REJECTED_DATES: [&str; 3] = ["2016-02-30", "2016-03-07x", "2016-03"];
+
+/// Benchmarks inputs that every strategy rejects, and inputs that only decode after the canonical
+/// description and `Rfc3339` have both failed, so the cost of each strategy's failure paths can be
+/// compared with its success path above. The iterations are called directly, as the
+/// `SqliteTemporalParser` implementations add a diagnosis on failure that is timed on its own
+/// below.
+fn bench_time_parsing_failures(c: &mut Criterion) {
+    let mut group = c.benchmark_group("OffsetDateTime Fallback");
+    for (i, expected) in [
+        ("2013-09-17 23:59-01:00", datetime!(2013-09-17 23:59 -01:00)),
+        (
+            "2015-11-19 01:01:39+01:00",
+            datetime!(2015-11-19 01:01:39 +01:00),
+        ),
+        ("2017-04-11T14:35+02:00", datetime!(2017-04-11 14:35 +02:00)),
+    ] {
+        bench_iterations!(&mut group, i, Decodes(expected), odt_iteration);
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("OffsetDateTime Rejected");
+    for i in GARBAGE.into_iter().chain(REJECTED_OFFSET_DATE_TIMES) {
+        bench_iterations!(&mut group, i, Rejected, odt_iteration);
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("PrimitiveDateTime Rejected");
+    for i in GARBAGE.into_iter().chain(REJECTED_PRIMITIVE_DATE_TIMES) {
+        bench_iterations!(&mut group, i, Rejected, pdt_iteration);
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("Time Rejected");
+    for i in GARBAGE.into_iter().chain(REJECTED_TIMES) {
+        bench_iterations!(&mut group, i, Rejected, time_iteration);
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("Date Rejected");
+    for i in GARBAGE.into_iter().chain(REJECTED_DATES) {
+        bench_iterations!(&mut group, i, Rejected, date_iteration);
+    }
+    group.finish();
+}
+
+/// Benchmarks the rejected inputs above through `SqliteTemporalParser`, which also explains the
+/// failure by walking every candidate format. The difference from the `Rejected` groups is the
+/// cost of the diagnosis.
+fn bench_time_parsing_diagnosis(c: &mut Criterion) {
+    let mut group = c.benchmark_group("OffsetDateTime Diagnosis");
+    for i in GARBAGE.into_iter().chain(REJECTED_OFFSET_DATE_TIMES) {
+        bench_strategies!(&mut group, i, Rejected, parse_offset_date_time);
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("PrimitiveDateTime Diagnosis");
+    for i in GARBAGE.into_iter().chain(REJECTED_PRIMITIVE_DATE_TIMES) {
+        bench_strategies!(&mut group, i, Rejected, parse_primitive_date_time);
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("Time Diagnosis");
+    for i in GARBAGE.into_iter().chain(REJECTED_TIMES) {
+        bench_strategies!(&mut group, i, Rejected, parse_time);
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("Date Diagnosis");
+    for i in GARBAGE.into_iter().chain(REJECTED_DATES) {
+        bench_strategies!(&mut group, i, Rejected, parse_date);
     }
     group.finish();
 }
 
-criterion_group!(benches, bench_time_parsing);
+criterion_group!(
+    benches,
+    bench_time_parsing,
+    bench_time_parsing_failures,
+    bench_time_parsing_diagnosis
+);
 criterion_main!(benches);
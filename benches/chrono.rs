@@ -0,0 +1,149 @@
+//! Benchmarks the chrono decoding in sqlx's SQLite support, which the `first` iteration was
+//! ported from, next to the `time` iterations on the inputs of `benchmark.rs`.
+//!
+//! The chrono functions are copied from
+//! [sqlx](https://github.com/launchbadge/sqlx/blob/59ad2ecc92b3c390115b19aeabc217ea7bdf4f05/sqlx-core/src/sqlite/types/chrono.rs)
+//! with only the error handling changed: `Rfc3339` first, then a loop over `strftime` formats,
+//! each tried as a `DateTime` with an offset and then as a `NaiveDateTime`. Their results are
+//! converted to `time` values to check them against the same expected values as the iterations.
+#![allow(unused)]
+#[macro_use]
+mod common;
+
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone,
+    Timelike, Utc,
+};
+use common::Expected::Decodes;
+use criterion::{criterion_group, criterion_main, Criterion};
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+use time_tests::*;
+
+fn decode_datetime_from_text(value: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt);
+    }
+
+    // Loop over common date time patterns, inspired by Diesel
+    let sqlite_datetime_formats = &[
+        // Most likely format
+        "%F %T%.f",
+        // Other formats in order of appearance in docs
+        "%F %R",
+        "%F %RZ",
+        "%F %R%:z",
+        "%F %T%.fZ",
+        "%F %T%.f%:z",
+        "%FT%R",
+        "%FT%RZ",
+        "%FT%R%:z",
+        "%FT%T%.f",
+        "%FT%T%.fZ",
+        "%FT%T%.f%:z",
+    ];
+
+    for format in sqlite_datetime_formats {
+        if let Ok(dt) = DateTime::parse_from_str(value, format) {
+            return Some(dt);
+        }
+
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(Utc.fix().from_utc_datetime(&dt));
+        }
+    }
+
+    None
+}
+
+fn decode_naive_date_time(value: &str) -> Option<NaiveDateTime> {
+    decode_datetime_from_text(value).map(|dt| dt.naive_local())
+}
+
+fn decode_naive_time(value: &str) -> Option<NaiveTime> {
+    // Loop over common time patterns, inspired by Diesel
+    let sqlite_time_formats = &[
+        // Most likely format
+        "%T.f", "%T%.f", // Other formats in order of appearance in docs
+        "%R", "%RZ", "%T%.fZ", "%R%:z", "%T%.f%:z",
+    ];
+
+    for format in sqlite_time_formats {
+        if let Ok(dt) = NaiveTime::parse_from_str(value, format) {
+            return Some(dt);
+        }
+    }
+
+    None
+}
+
+fn decode_naive_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%F").ok()
+}
+
+fn to_date(date: NaiveDate) -> Date {
+    Date::from_ordinal_date(date.year(), date.ordinal() as u16).unwrap()
+}
+
+fn to_time(time: NaiveTime) -> Time {
+    Time::from_hms_nano(
+        time.hour() as u8,
+        time.minute() as u8,
+        time.second() as u8,
+        time.nanosecond(),
+    )
+    .unwrap()
+}
+
+fn to_primitive_date_time(dt: NaiveDateTime) -> PrimitiveDateTime {
+    PrimitiveDateTime::new(to_date(dt.date()), to_time(dt.time()))
+}
+
+fn to_offset_date_time(dt: DateTime<FixedOffset>) -> OffsetDateTime {
+    let offset = UtcOffset::from_whole_seconds(dt.offset().local_minus_utc()).unwrap();
+    to_primitive_date_time(dt.naive_local()).assume_offset(offset)
+}
+
+fn bench_chrono_parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Chrono OffsetDateTime");
+    for (i, expected) in common::OFFSET_DATE_TIMES {
+        common::bench_strategy(&mut group, "chrono", i, Decodes(expected), |i| {
+            decode_datetime_from_text(i)
+                .map(to_offset_date_time)
+                .ok_or("rejected")
+        });
+        bench_strategies!(&mut group, i, Decodes(expected), parse_offset_date_time);
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("Chrono PrimitiveDateTime");
+    for (i, expected) in common::PRIMITIVE_DATE_TIMES {
+        common::bench_strategy(&mut group, "chrono", i, Decodes(expected), |i| {
+            decode_naive_date_time(i)
+                .map(to_primitive_date_time)
+                .ok_or("rejected")
+        });
+        bench_strategies!(&mut group, i, Decodes(expected), parse_primitive_date_time);
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("Chrono Time");
+    for (i, expected) in common::TIMES {
+        common::bench_strategy(&mut group, "chrono", i, Decodes(expected), |i| {
+            decode_naive_time(i).map(to_time).ok_or("rejected")
+        });
+        bench_strategies!(&mut group, i, Decodes(expected), parse_time);
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("Chrono Date");
+    for (i, expected) in common::DATES {
+        common::bench_strategy(&mut group, "chrono", i, Decodes(expected), |i| {
+            decode_naive_date(i).map(to_date).ok_or("rejected")
+        });
+        bench_strategies!(&mut group, i, Decodes(expected), parse_date);
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_chrono_parsing);
+criterion_main!(benches);
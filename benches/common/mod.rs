@@ -0,0 +1,165 @@
+//! Inputs and helpers shared by the decoding benchmarks.
+#![allow(dead_code, unused_macros)]
+
+use criterion::measurement::WallTime;
+use criterion::{BenchmarkGroup, BenchmarkId};
+use std::fmt::Debug;
+use time::macros::{date, datetime, time};
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+use time_tests::{First, Fourth, Second, SqliteTemporalParser, Third};
+
+/// Each input with what it decodes to.
+pub const OFFSET_DATE_TIMES: [(&str, OffsetDateTime); 5] = [
+    (
+        "2016-03-07T22:36:55.135+03:30",
+        datetime!(2016-03-07 22:36:55.135 +03:30),
+    ),
+    ("2013-09-17 23:59-01:00", datetime!(2013-09-17 23:59 -01:00)),
+    (
+        "2015-11-19 01:01:39+01:00",
+        datetime!(2015-11-19 01:01:39 +01:00),
+    ),
+    (
+        "2014-10-18 00:00:38.697+00:00",
+        datetime!(2014-10-18 00:00:38.697 UTC),
+    ),
+    ("2017-04-11T14:35+02:00", datetime!(2017-04-11 14:35 +02:00)),
+];
+
+pub const PRIMITIVE_DATE_TIMES: [(&str, PrimitiveDateTime); 12] = [
+    (
+        "2018-12-01 04:09:19.543",
+        datetime!(2018-12-01 04:09:19.543),
+    ),
+    ("2017-11-30 03:08", datetime!(2017-11-30 03:08)),
+    ("2011-05-24 21:02Z", datetime!(2011-05-24 21:02)),
+    ("2019-01-02 05:10:20", datetime!(2019-01-02 05:10:20)),
+    ("2013-07-26 23:04:14Z", datetime!(2013-07-26 23:04:14)),
+    (
+        "2012-06-25 22:03:13.321Z",
+        datetime!(2012-06-25 22:03:13.321),
+    ),
+    ("2014-08-27T00:05", datetime!(2014-08-27 00:05)),
+    ("2008-02-21T18:59Z", datetime!(2008-02-21 18:59)),
+    ("2016-10-29T02:07:17", datetime!(2016-10-29 02:07:17)),
+    ("2010-04-23T20:01:11Z", datetime!(2010-04-23 20:01:11)),
+    (
+        "2015-09-28T01:06:16.432",
+        datetime!(2015-09-28 01:06:16.432),
+    ),
+    ("2009-03-22T19:00:10.21Z", datetime!(2009-03-22 19:00:10.21)),
+];
+
+pub const TIMES: [(&str, Time); 3] = [
+    ("20:45:31.133", time!(20:45:31.133)),
+    ("21:46:32", time!(21:46:32)),
+    ("19:44", time!(19:44)),
+];
+
+pub const DATES: [(&str, Date); 2] = [
+    ("2016-03-07", date!(2016 - 03 - 07)),
+    ("0001-01-01", date!(0001 - 01 - 01)),
+];
+
+/// Each iteration strategy, under the name `bench_strategies!` gives it, for benchmarks that loop
+/// over them.
+pub const STRATEGIES: [(&str, &dyn SqliteTemporalParser); 4] = [
+    ("1st ITER", &First),
+    ("2nd ITER", &Second),
+    ("3rd ITER", &Third),
+    ("4th ITER", &Fourth),
+];
+
+/// What a strategy should return for an input.
+#[derive(Debug, Clone, Copy)]
+pub enum Expected<T> {
+    Decodes(T),
+    Rejected,
+}
+
+/// Benchmarks `$parse` on `$input` with every iteration strategy, after checking that each
+/// returns what is `$expected`.
+macro_rules! bench_strategies {
+    ($group:expr, $input:expr, $expected:expr, $parse:ident) => {
+        let expected = $expected;
+        common::bench_strategy($group, "1st ITER", $input, expected, |i| First.$parse(i));
+        common::bench_strategy($group, "2nd ITER", $input, expected, |i| Second.$parse(i));
+        common::bench_strategy($group, "3rd ITER", $input, expected, |i| Third.$parse(i));
+        common::bench_strategy($group, "4th ITER", $input, expected, |i| Fourth.$parse(i));
+    };
+}
+
+/// Like `bench_strategies!`, but calls each iteration's `$iteration` function directly. These
+/// return `time`'s own error, so a rejected input is timed without the diagnosis the
+/// `SqliteTemporalParser` implementations build from every candidate format.
+macro_rules! bench_iterations {
+    ($group:expr, $input:expr, $expected:expr, $iteration:ident) => {
+        let expected = $expected;
+        common::bench_strategy($group, "1st ITER", $input, expected, |i| {
+            first::$iteration(i).ok_or(())
+        });
+        common::bench_strategy($group, "2nd ITER", $input, expected, second::$iteration);
+        common::bench_strategy($group, "3rd ITER", $input, expected, third::$iteration);
+        common::bench_strategy($group, "4th ITER", $input, expected, fourth::$iteration);
+    };
+}
+
+/// Equality as the checks use it.
+pub trait Same {
+    fn same(&self, other: &Self) -> bool;
+}
+
+/// `OffsetDateTime` equality compares instants, so the offsets are compared as well.
+impl Same for OffsetDateTime {
+    fn same(&self, other: &Self) -> bool {
+        (self, self.offset()) == (other, other.offset())
+    }
+}
+
+impl Same for PrimitiveDateTime {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+impl Same for Time {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+impl Same for Date {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+/// Panics unless `strategy` returned what is `expected` for `input`, as timing a wrong result
+/// would compare unlike work.
+pub fn check<T: Debug + Same, E: Debug>(
+    strategy: &str,
+    input: &str,
+    expected: &Expected<T>,
+    result: &Result<T, E>,
+) {
+    match (expected, result) {
+        (Expected::Decodes(value), Ok(actual)) if actual.same(value) => {}
+        (Expected::Rejected, Err(_)) => {}
+        _ => panic!("{strategy} returned {result:?} for `{input}`, expected {expected:?}"),
+    }
+}
+
+/// Benchmarks `parse` on `input`, first checking that it returns what is `expected`.
+pub fn bench_strategy<T: Debug + Same, E: Debug>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    strategy: &str,
+    input: &str,
+    expected: Expected<T>,
+    parse: impl Fn(&str) -> Result<T, E>,
+) {
+    check(strategy, input, &expected, &parse(input));
+
+    group.bench_with_input(BenchmarkId::new(strategy, input), input, |b, i| {
+        b.iter(|| parse(i))
+    });
+}
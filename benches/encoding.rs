@@ -0,0 +1,284 @@
+//! Benchmarks encoding values in each of SQLite's time value formats, comparing `time`'s
+//! `format_description!`, this crate's encoders, `well_known::Rfc3339` and an encoder written with
+//! `write!`.
+use criterion::measurement::WallTime;
+use criterion::{
+    black_box, criterion_group, criterion_main, BenchmarkGroup, BenchmarkId, Criterion,
+};
+use std::fmt::Write;
+use time::format_description::well_known::Rfc3339;
+use time::format_description::FormatItem;
+use time::macros::{datetime, format_description as fd};
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+use time_tests::encode;
+use time_tests::matched::{Precision, Separator};
+
+/// One of SQLite's formats, with the description `time` formats it with.
+struct Layout {
+    name: &'static str,
+    separator: Separator,
+    precision: Precision,
+    description: &'static [FormatItem<'static>],
+}
+
+const MILLISECONDS: Precision = Precision::Subseconds(3);
+
+/// Formats 2 to 7, followed by a numeric offset.
+const OFFSET_DATE_TIME_LAYOUTS: [Layout; 6] = [
+    Layout {
+        name: "YYYY-MM-DD HH:MM+HH:MM",
+        separator: Separator::Space,
+        precision: Precision::Minutes,
+        description: fd!(
+            "[year]-[month]-[day] [hour]:[minute][offset_hour sign:mandatory]:[offset_minute]"
+        ),
+    },
+    Layout {
+        name: "YYYY-MM-DD HH:MM:SS+HH:MM",
+        separator: Separator::Space,
+        precision: Precision::Seconds,
+        description: fd!("[year]-[month]-[day] [hour]:[minute]:[second][offset_hour sign:mandatory]:[offset_minute]"),
+    },
+    Layout {
+        name: "YYYY-MM-DD HH:MM:SS.SSS+HH:MM",
+        separator: Separator::Space,
+        precision: MILLISECONDS,
+        description: fd!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:3][offset_hour sign:mandatory]:[offset_minute]"),
+    },
+    Layout {
+        name: "YYYY-MM-DDTHH:MM+HH:MM",
+        separator: Separator::T,
+        precision: Precision::Minutes,
+        description: fd!(
+            "[year]-[month]-[day]T[hour]:[minute][offset_hour sign:mandatory]:[offset_minute]"
+        ),
+    },
+    Layout {
+        name: "YYYY-MM-DDTHH:MM:SS+HH:MM",
+        separator: Separator::T,
+        precision: Precision::Seconds,
+        description: fd!("[year]-[month]-[day]T[hour]:[minute]:[second][offset_hour sign:mandatory]:[offset_minute]"),
+    },
+    Layout {
+        name: "YYYY-MM-DDTHH:MM:SS.SSS+HH:MM",
+        separator: Separator::T,
+        precision: MILLISECONDS,
+        description: fd!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3][offset_hour sign:mandatory]:[offset_minute]"),
+    },
+];
+
+/// Formats 2 to 7.
+const PRIMITIVE_DATE_TIME_LAYOUTS: [Layout; 6] = [
+    Layout {
+        name: "YYYY-MM-DD HH:MM",
+        separator: Separator::Space,
+        precision: Precision::Minutes,
+        description: fd!("[year]-[month]-[day] [hour]:[minute]"),
+    },
+    Layout {
+        name: "YYYY-MM-DD HH:MM:SS",
+        separator: Separator::Space,
+        precision: Precision::Seconds,
+        description: fd!("[year]-[month]-[day] [hour]:[minute]:[second]"),
+    },
+    Layout {
+        name: "YYYY-MM-DD HH:MM:SS.SSS",
+        separator: Separator::Space,
+        precision: MILLISECONDS,
+        description: fd!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:3]"),
+    },
+    Layout {
+        name: "YYYY-MM-DDTHH:MM",
+        separator: Separator::T,
+        precision: Precision::Minutes,
+        description: fd!("[year]-[month]-[day]T[hour]:[minute]"),
+    },
+    Layout {
+        name: "YYYY-MM-DDTHH:MM:SS",
+        separator: Separator::T,
+        precision: Precision::Seconds,
+        description: fd!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
+    },
+    Layout {
+        name: "YYYY-MM-DDTHH:MM:SS.SSS",
+        separator: Separator::T,
+        precision: MILLISECONDS,
+        description: fd!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]"),
+    },
+];
+
+/// Formats 8 to 10.
+const TIME_LAYOUTS: [Layout; 3] = [
+    Layout {
+        name: "HH:MM",
+        separator: Separator::None,
+        precision: Precision::Minutes,
+        description: fd!("[hour]:[minute]"),
+    },
+    Layout {
+        name: "HH:MM:SS",
+        separator: Separator::None,
+        precision: Precision::Seconds,
+        description: fd!("[hour]:[minute]:[second]"),
+    },
+    Layout {
+        name: "HH:MM:SS.SSS",
+        separator: Separator::None,
+        precision: MILLISECONDS,
+        description: fd!("[hour]:[minute]:[second].[subsecond digits:3]"),
+    },
+];
+
+fn bench_encoder<T: Copy, R>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    encoder: &str,
+    layout: &str,
+    value: T,
+    encode: impl Fn(T) -> R,
+) {
+    group.bench_function(BenchmarkId::new(encoder, layout), |b| {
+        b.iter(|| encode(black_box(value)))
+    });
+}
+
+fn write_date(text: &mut String, date: Date, separator: Separator) {
+    let separator = match separator {
+        Separator::Space => " ",
+        Separator::T => "T",
+        Separator::None => "",
+        Separator::SpaceAndT => " T",
+    };
+    write!(
+        text,
+        "{:04}-{:02}-{:02}{separator}",
+        date.year(),
+        date.month() as u8,
+        date.day()
+    )
+    .unwrap();
+}
+
+fn write_time(text: &mut String, time: Time, precision: Precision) {
+    write!(text, "{:02}:{:02}", time.hour(), time.minute()).unwrap();
+    match precision {
+        Precision::Minutes => {}
+        Precision::Seconds => write!(text, ":{:02}", time.second()).unwrap(),
+        Precision::Subseconds(_) => {
+            write!(text, ":{:02}.{:03}", time.second(), time.millisecond()).unwrap()
+        }
+    }
+}
+
+fn write_offset(text: &mut String, offset: UtcOffset) {
+    let (hours, minutes, _) = offset.as_hms();
+    let sign = if offset.is_negative() { '-' } else { '+' };
+    write!(text, "{sign}{:02}:{:02}", hours.abs(), minutes.abs()).unwrap();
+}
+
+fn hand_written_odt(date_time: OffsetDateTime, layout: &Layout) -> String {
+    let mut text = String::with_capacity(29);
+    write_date(&mut text, date_time.date(), layout.separator);
+    write_time(&mut text, date_time.time(), layout.precision);
+    write_offset(&mut text, date_time.offset());
+    text
+}
+
+fn hand_written_pdt(date_time: PrimitiveDateTime, layout: &Layout) -> String {
+    let mut text = String::with_capacity(23);
+    write_date(&mut text, date_time.date(), layout.separator);
+    write_time(&mut text, date_time.time(), layout.precision);
+    text
+}
+
+fn hand_written_time(time: Time, layout: &Layout) -> String {
+    let mut text = String::with_capacity(12);
+    write_time(&mut text, time, layout.precision);
+    text
+}
+
+fn bench_time_formatting(c: &mut Criterion) {
+    let date_time = datetime!(2016-03-07 22:36:55.135 +03:30);
+
+    let mut group = c.benchmark_group("Encode OffsetDateTime");
+    for layout in &OFFSET_DATE_TIME_LAYOUTS {
+        bench_encoder(
+            &mut group,
+            "format_description!",
+            layout.name,
+            date_time,
+            |v| v.format(layout.description),
+        );
+        bench_encoder(&mut group, "write!", layout.name, date_time, |v| {
+            hand_written_odt(v, layout)
+        });
+    }
+    bench_encoder(
+        &mut group,
+        "encode",
+        "default",
+        date_time,
+        encode::odt_default,
+    );
+    bench_encoder(
+        &mut group,
+        "encode",
+        "sortable",
+        date_time,
+        encode::odt_sortable,
+    );
+    bench_encoder(
+        &mut group,
+        "Rfc3339",
+        "YYYY-MM-DDTHH:MM:SS.SSS+HH:MM",
+        date_time,
+        |v| v.format(&Rfc3339),
+    );
+    group.finish();
+
+    let date_time = PrimitiveDateTime::new(date_time.date(), date_time.time());
+    let mut group = c.benchmark_group("Encode PrimitiveDateTime");
+    for layout in &PRIMITIVE_DATE_TIME_LAYOUTS {
+        bench_encoder(
+            &mut group,
+            "format_description!",
+            layout.name,
+            date_time,
+            |v| v.format(layout.description),
+        );
+        bench_encoder(&mut group, "write!", layout.name, date_time, |v| {
+            hand_written_pdt(v, layout)
+        });
+    }
+    bench_encoder(
+        &mut group,
+        "encode",
+        "default",
+        date_time,
+        encode::pdt_default,
+    );
+    // `Rfc3339` needs an offset, so it can only write a UTC value with a `Z` after it
+    bench_encoder(
+        &mut group,
+        "Rfc3339",
+        "YYYY-MM-DDTHH:MM:SS.SSSZ",
+        date_time,
+        |v| v.assume_utc().format(&Rfc3339),
+    );
+    group.finish();
+
+    let time = date_time.time();
+    let mut group = c.benchmark_group("Encode Time");
+    for layout in &TIME_LAYOUTS {
+        bench_encoder(&mut group, "format_description!", layout.name, time, |v| {
+            v.format(layout.description)
+        });
+        bench_encoder(&mut group, "write!", layout.name, time, |v| {
+            hand_written_time(v, layout)
+        });
+    }
+    bench_encoder(&mut group, "encode", "default", time, encode::time_default);
+    group.finish();
+}
+
+criterion_group!(benches, bench_time_formatting);
+criterion_main!(benches);
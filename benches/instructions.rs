@@ -0,0 +1,272 @@
+//! Counts the instructions and cache accesses of each iteration on the inputs of `benchmark.rs`,
+//! running each decode once under Callgrind.
+//!
+//! The counts do not depend on the machine's load, so they are comparable between runs on shared
+//! runners where timings are not. Running it needs `valgrind` on the `PATH`:
+//!
+//! ```text
+//! cargo bench --bench instructions
+//! cargo bench --bench instructions -- "4th ITER"
+//! ```
+//!
+//! An argument only counts the cases whose name contains it. The binary runs itself again under
+//! Callgrind for each case, with collection switched on only inside [`callgrind_case`], so the
+//! counts leave out the process's start and the dispatch to the case. Callgrind's output is kept in
+//! `target/callgrind`, and the change from the previous run is printed beside each count.
+//!
+//! Every case is checked to decode to its expected value, as in `benchmark.rs`, before any are
+//! counted.
+mod common;
+
+use common::Expected::Decodes;
+use common::STRATEGIES;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use time_tests::{First, Fourth, Second, SqliteTemporalParser, Third};
+
+fn odt<P: SqliteTemporalParser + Default, const I: usize>() -> impl Sized {
+    P::default().parse_offset_date_time(black_box(common::OFFSET_DATE_TIMES[I].0))
+}
+
+fn pdt<P: SqliteTemporalParser + Default, const I: usize>() -> impl Sized {
+    P::default().parse_primitive_date_time(black_box(common::PRIMITIVE_DATE_TIMES[I].0))
+}
+
+fn time<P: SqliteTemporalParser + Default, const I: usize>() -> impl Sized {
+    P::default().parse_time(black_box(common::TIMES[I].0))
+}
+
+fn date<P: SqliteTemporalParser + Default, const I: usize>() -> impl Sized {
+    P::default().parse_date(black_box(common::DATES[I].0))
+}
+
+/// A benchmark of each strategy on each input, as the group, strategy, input and a function that
+/// decodes it. Each case runs in its own process, so each needs its own function.
+macro_rules! cases {
+    ($group:literal, $inputs:expr, $decode:ident, [$($i:literal),*]) => {
+        [$(
+            ($group, "1st ITER", $inputs[$i].0, (|| drop(black_box($decode::<First, $i>()))) as fn()),
+            ($group, "2nd ITER", $inputs[$i].0, (|| drop(black_box($decode::<Second, $i>()))) as fn()),
+            ($group, "3rd ITER", $inputs[$i].0, (|| drop(black_box($decode::<Third, $i>()))) as fn()),
+            ($group, "4th ITER", $inputs[$i].0, (|| drop(black_box($decode::<Fourth, $i>()))) as fn()),
+        )*]
+    };
+}
+
+/// Checks every strategy on every input the cases count.
+fn check() {
+    for (strategy, parser) in STRATEGIES {
+        for (input, expected) in common::OFFSET_DATE_TIMES {
+            let result = parser.parse_offset_date_time(input);
+            common::check(strategy, input, &Decodes(expected), &result);
+        }
+        for (input, expected) in common::PRIMITIVE_DATE_TIMES {
+            let result = parser.parse_primitive_date_time(input);
+            common::check(strategy, input, &Decodes(expected), &result);
+        }
+        for (input, expected) in common::TIMES {
+            common::check(
+                strategy,
+                input,
+                &Decodes(expected),
+                &parser.parse_time(input),
+            );
+        }
+        for (input, expected) in common::DATES {
+            common::check(
+                strategy,
+                input,
+                &Decodes(expected),
+                &parser.parse_date(input),
+            );
+        }
+    }
+}
+
+/// The function Callgrind collects inside of, named in `--toggle-collect`.
+#[no_mangle]
+#[inline(never)]
+pub fn callgrind_case(run: fn()) {
+    run();
+}
+
+/// The counts of one case, from the `totals:` line of Callgrind's output.
+struct Counts {
+    events: HashMap<String, u64>,
+}
+
+impl Counts {
+    fn read(path: &Path) -> Option<Self> {
+        let out = fs::read_to_string(path).ok()?;
+        let names = out.lines().find_map(|line| line.strip_prefix("events:"))?;
+        let totals = out.lines().rev().find_map(|line| {
+            line.strip_prefix("totals:")
+                .or(line.strip_prefix("summary:"))
+        })?;
+        let events = names
+            .split_whitespace()
+            .map(str::to_owned)
+            .zip(
+                totals
+                    .split_whitespace()
+                    .map(|count| count.parse().unwrap()),
+            )
+            .collect();
+        Some(Self { events })
+    }
+
+    fn event(&self, name: &str) -> u64 {
+        self.events.get(name).copied().unwrap_or(0)
+    }
+
+    fn instructions(&self) -> u64 {
+        self.event("Ir")
+    }
+
+    fn ram(&self) -> u64 {
+        self.event("ILmr") + self.event("DLmr") + self.event("DLmw")
+    }
+
+    fn last_level(&self) -> u64 {
+        self.event("I1mr") + self.event("D1mr") + self.event("D1mw") - self.ram()
+    }
+
+    fn l1(&self) -> u64 {
+        self.event("Ir") + self.event("Dr") + self.event("Dw") - self.last_level() - self.ram()
+    }
+
+    /// Itamar Turner-Trauring's estimate from the L1, last level and RAM accesses.
+    fn cycles(&self) -> u64 {
+        self.l1() + 5 * self.last_level() + 35 * self.ram()
+    }
+}
+
+/// One of the figures printed for each case.
+type Count = fn(&Counts) -> u64;
+
+/// Runs case `index` under Callgrind and returns its counts and those of the previous run.
+fn count(arch: &str, index: usize, name: &str) -> (Counts, Option<Counts>) {
+    let dir = PathBuf::from("target/callgrind");
+    fs::create_dir_all(&dir).unwrap();
+    let file: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let out = dir.join(format!("{file}.out"));
+    let old = dir.join(format!("{file}.out.old"));
+    if out.exists() {
+        fs::rename(&out, &old).unwrap();
+    }
+
+    // `setarch -R` turns off address randomisation, which would move the cache misses between runs.
+    // The cache sizes are fixed so that the counts do not depend on the machine's.
+    let status = Command::new("setarch")
+        .args([
+            arch,
+            "-R",
+            "valgrind",
+            "--tool=callgrind",
+            "--cache-sim=yes",
+        ])
+        .args(["--I1=32768,8,64", "--D1=32768,8,64", "--LL=8388608,16,64"])
+        .arg("--toggle-collect=callgrind_case")
+        .arg(format!("--callgrind-out-file={}", out.display()))
+        .arg(env::current_exe().unwrap())
+        .args(["--callgrind-run", &index.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success(), "Callgrind failed on {name}: {status}");
+
+    let counts = Counts::read(&out).expect("Callgrind should write its totals");
+    (counts, Counts::read(&old))
+}
+
+fn change(new: u64, old: Option<u64>) -> String {
+    match old {
+        None => String::new(),
+        Some(old) if old == new => " (no change)".to_owned(),
+        Some(old) => format!(" ({:+.3}%)", (new as f64 - old as f64) / old as f64 * 100.0),
+    }
+}
+
+fn main() {
+    let odt = cases!(
+        "OffsetDateTime",
+        common::OFFSET_DATE_TIMES,
+        odt,
+        [0, 1, 2, 3, 4]
+    );
+    let pdt = cases!(
+        "PrimitiveDateTime",
+        common::PRIMITIVE_DATE_TIMES,
+        pdt,
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+    );
+    let time = cases!("Time", common::TIMES, time, [0, 1, 2]);
+    let date = cases!("Date", common::DATES, date, [0, 1]);
+
+    // Every input of `benchmark.rs` should be covered
+    assert_eq!(odt.len(), 4 * common::OFFSET_DATE_TIMES.len());
+    assert_eq!(pdt.len(), 4 * common::PRIMITIVE_DATE_TIMES.len());
+    assert_eq!(time.len(), 4 * common::TIMES.len());
+    assert_eq!(date.len(), 4 * common::DATES.len());
+
+    let benches: Vec<(String, fn())> = [&odt[..], &pdt, &time, &date]
+        .concat()
+        .into_iter()
+        .map(|(group, strategy, input, run)| (format!("{group} {strategy} {input}"), run))
+        .collect();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let [flag, index] = &args[..] {
+        if flag == "--callgrind-run" {
+            // Running under Callgrind: decode the one case and leave
+            callgrind_case(benches[index.parse::<usize>().unwrap()].1);
+            return;
+        }
+    }
+
+    check();
+    let valgrind = Command::new("valgrind")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .status();
+    if !valgrind.is_ok_and(|status| status.success()) {
+        println!("valgrind is not on the PATH, so the cases were checked but not counted");
+        return;
+    }
+
+    // `cargo bench` passes `--bench`; any other argument filters the cases by name
+    let filter = args.iter().find(|arg| !arg.starts_with("--"));
+    let arch = Command::new("uname").arg("-m").output().unwrap().stdout;
+    let arch = String::from_utf8(arch).unwrap();
+    for (index, (name, _)) in benches.iter().enumerate() {
+        if filter.is_some_and(|filter| !name.contains(filter.as_str())) {
+            continue;
+        }
+        let (new, old) = count(arch.trim(), index, name);
+        let rows: [(&str, Count); 5] = [
+            ("Instructions", Counts::instructions),
+            ("L1 accesses", Counts::l1),
+            ("LL accesses", Counts::last_level),
+            ("RAM accesses", Counts::ram),
+            ("Estimated cycles", Counts::cycles),
+        ];
+        println!("{name}");
+        for (label, of) in rows {
+            let label = format!("{label}:");
+            let count = of(&new);
+            println!(
+                "  {label:<18}{count:>12}{}",
+                change(count, old.as_ref().map(of))
+            );
+        }
+        println!();
+    }
+}
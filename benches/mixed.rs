@@ -0,0 +1,257 @@
+//! Benchmarks decoding a shuffled corpus that mixes the canonical format with legacy formats.
+//!
+//! `benchmark.rs` decodes one string over and over, which lets the branch predictor learn the
+//! path each strategy takes through its format descriptions. Here every iteration decodes the
+//! whole corpus, so a strategy that branches on the format pays for mispredictions as it would
+//! reading a real table.
+//!
+//! The canonical format is what `encode` writes with the enabled `default-format-*` features. The
+//! share of canonical values in each corpus is set by `MIXED_CANONICAL_PERCENT`, a comma separated
+//! list of percentages, and defaults to `100,90,50`. The rest are spread evenly over the legacy
+//! formats.
+//!
+//! Every value in a corpus is checked to decode to what it was written from before the corpus is
+//! timed.
+mod common;
+
+use common::Expected::Decodes;
+use common::{Same, STRATEGIES};
+use criterion::measurement::WallTime;
+use criterion::{
+    black_box, criterion_group, criterion_main, BenchmarkGroup, BenchmarkId, Criterion, Throughput,
+};
+use proptest::prelude::RngExt;
+use proptest::test_runner::{RngAlgorithm, TestRng};
+use std::env;
+use std::fmt::Debug;
+use time::format_description::FormatItem;
+use time::macros::format_description as fd;
+use time::{Duration, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+use time_tests::{encode, SqliteTemporalParser};
+
+const CORPUS_SIZE: usize = 1024;
+
+/// The smallest unit a format writes.
+#[derive(Clone, Copy)]
+enum Precision {
+    Minutes,
+    Seconds,
+    Milliseconds,
+}
+
+/// A legacy format, with the precision it keeps.
+type Layout = (&'static [FormatItem<'static>], Precision);
+
+const OFFSET_DATE_TIME_LEGACY: [Layout; 5] = [
+    (
+        fd!("[year]-[month]-[day] [hour]:[minute][offset_hour sign:mandatory]:[offset_minute]"),
+        Precision::Minutes,
+    ),
+    (
+        fd!("[year]-[month]-[day] [hour]:[minute]:[second][offset_hour sign:mandatory]:[offset_minute]"),
+        Precision::Seconds,
+    ),
+    (
+        fd!("[year]-[month]-[day]T[hour]:[minute][offset_hour sign:mandatory]:[offset_minute]"),
+        Precision::Minutes,
+    ),
+    (
+        fd!("[year]-[month]-[day]T[hour]:[minute]:[second][offset_hour sign:mandatory]:[offset_minute]"),
+        Precision::Seconds,
+    ),
+    (
+        fd!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3][offset_hour sign:mandatory]:[offset_minute]"),
+        Precision::Milliseconds,
+    ),
+];
+
+const PRIMITIVE_DATE_TIME_LEGACY: [Layout; 6] = [
+    (
+        fd!("[year]-[month]-[day] [hour]:[minute]"),
+        Precision::Minutes,
+    ),
+    (
+        fd!("[year]-[month]-[day] [hour]:[minute]:[second]"),
+        Precision::Seconds,
+    ),
+    (
+        fd!("[year]-[month]-[day]T[hour]:[minute]"),
+        Precision::Minutes,
+    ),
+    (
+        fd!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
+        Precision::Seconds,
+    ),
+    (
+        fd!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]"),
+        Precision::Milliseconds,
+    ),
+    (
+        fd!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"),
+        Precision::Milliseconds,
+    ),
+];
+
+const TIME_LEGACY: [Layout; 2] = [
+    (fd!("[hour]:[minute]"), Precision::Minutes),
+    (fd!("[hour]:[minute]:[second]"), Precision::Seconds),
+];
+
+/// A value between 1970 and 2099 with whole milliseconds, as SQLite keeps, and an offset of whole
+/// quarter hours.
+fn date_time(rng: &mut TestRng) -> OffsetDateTime {
+    let milliseconds = rng.random_range(0..4_102_444_800_000);
+    let quarter_hours = rng.random_range(-56..=56);
+    let offset = UtcOffset::from_whole_seconds(quarter_hours * 15 * 60).unwrap();
+    (OffsetDateTime::UNIX_EPOCH + Duration::milliseconds(milliseconds)).to_offset(offset)
+}
+
+/// The canonical percentages from `MIXED_CANONICAL_PERCENT`.
+fn mixes() -> Vec<usize> {
+    let Ok(mixes) = env::var("MIXED_CANONICAL_PERCENT") else {
+        return vec![100, 90, 50];
+    };
+    mixes
+        .split(',')
+        .map(|percent| {
+            percent
+                .trim()
+                .parse()
+                .ok()
+                .filter(|percent| *percent <= 100)
+                .unwrap_or_else(|| panic!("MIXED_CANONICAL_PERCENT: bad percentage `{percent}`"))
+        })
+        .collect()
+}
+
+/// `dt` without what `precision` leaves out.
+fn truncate(dt: OffsetDateTime, precision: Precision) -> OffsetDateTime {
+    match precision {
+        Precision::Minutes => dt.replace_second(0).unwrap().replace_millisecond(0),
+        Precision::Seconds => dt.replace_millisecond(0),
+        Precision::Milliseconds => Ok(dt),
+    }
+    .unwrap()
+}
+
+/// `CORPUS_SIZE` generated values, `percent` of them rendered by `render` with `None` and the
+/// rest with each of the `legacy` descriptions in turn, in shuffled order. Each is paired with the
+/// value it was rendered from, less what its format leaves out; the canonical format keeps every
+/// millisecond. The values are random, but the same on every run.
+fn corpus(
+    percent: usize,
+    legacy: &[Layout],
+    render: impl Fn(OffsetDateTime, Option<&[FormatItem<'_>]>) -> String,
+) -> Vec<(String, OffsetDateTime)> {
+    let mut rng = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+    let canonical = CORPUS_SIZE * percent / 100;
+    let mut corpus: Vec<_> = (0..CORPUS_SIZE)
+        .map(|i| {
+            let dt = date_time(&mut rng);
+            match i.checked_sub(canonical) {
+                Some(i) => {
+                    let (layout, precision) = legacy[i % legacy.len()];
+                    (render(dt, Some(layout)), truncate(dt, precision))
+                }
+                None => (render(dt, None), dt),
+            }
+        })
+        .collect();
+
+    for i in (1..corpus.len()).rev() {
+        corpus.swap(i, rng.random_range(0..=i));
+    }
+    corpus
+}
+
+/// Benchmarks decoding every text of `corpus` with each strategy, after checking that each text
+/// decodes to its value, converted by `expect` to the decoded type.
+fn bench_corpus<T: Debug + Same, E: Debug>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    percent: usize,
+    corpus: &[(String, OffsetDateTime)],
+    expect: impl Fn(OffsetDateTime) -> T,
+    parse: impl Fn(&dyn SqliteTemporalParser, &str) -> Result<T, E>,
+) {
+    let corpus: Vec<_> = corpus
+        .iter()
+        .map(|(text, expected)| (text.as_str(), Decodes(expect(*expected))))
+        .collect();
+    for (strategy, parser) in STRATEGIES {
+        for (text, expected) in &corpus {
+            common::check(strategy, text, expected, &parse(parser, text));
+        }
+    }
+    let corpus: Vec<&str> = corpus.into_iter().map(|(text, _)| text).collect();
+
+    group.throughput(Throughput::Elements(corpus.len() as u64));
+    for (strategy, parser) in STRATEGIES {
+        let id = BenchmarkId::new(strategy, format!("{percent}% canonical"));
+        group.bench_with_input(id, &corpus, |b, corpus| {
+            b.iter(|| {
+                for text in corpus {
+                    let _ = black_box(parse(parser, black_box(text)));
+                }
+            })
+        });
+    }
+}
+
+fn bench_mixed_workload(c: &mut Criterion) {
+    let mixes = mixes();
+
+    let mut group = c.benchmark_group("Mixed OffsetDateTime");
+    for &percent in &mixes {
+        let corpus = corpus(
+            percent,
+            &OFFSET_DATE_TIME_LEGACY,
+            |dt, layout| match layout {
+                Some(layout) => dt.format(layout).unwrap(),
+                None => encode::odt_default(dt).unwrap(),
+            },
+        );
+        bench_corpus(
+            &mut group,
+            percent,
+            &corpus,
+            |dt| dt,
+            |p, i| p.parse_offset_date_time(i),
+        );
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("Mixed PrimitiveDateTime");
+    for &percent in &mixes {
+        let corpus = corpus(percent, &PRIMITIVE_DATE_TIME_LEGACY, |dt, layout| {
+            let dt = PrimitiveDateTime::new(dt.date(), dt.time());
+            match layout {
+                Some(layout) => dt.format(layout).unwrap(),
+                None => encode::pdt_default(dt).unwrap(),
+            }
+        });
+        let expect = |dt: OffsetDateTime| PrimitiveDateTime::new(dt.date(), dt.time());
+        bench_corpus(&mut group, percent, &corpus, expect, |p, i| {
+            p.parse_primitive_date_time(i)
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("Mixed Time");
+    for &percent in &mixes {
+        let corpus = corpus(percent, &TIME_LEGACY, |dt, layout| match layout {
+            Some(layout) => dt.time().format(layout).unwrap(),
+            None => encode::time_default(dt.time()).unwrap(),
+        });
+        bench_corpus(
+            &mut group,
+            percent,
+            &corpus,
+            OffsetDateTime::time,
+            |p, i| p.parse_time(i),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_mixed_workload);
+criterion_main!(benches);
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use time_tests_fuzz::{check, is_date};
+
+fuzz_target!(|data: &[u8]| {
+    check(data, is_date, |parser, input| parser.parse_date(input).ok());
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use time_tests_fuzz::{check, is_offset_date_time};
+
+fuzz_target!(|data: &[u8]| {
+    check(data, is_offset_date_time, |parser, input| {
+        // Compare offsets too, since `OffsetDateTime` equality only compares instants
+        let dt = parser.parse_offset_date_time(input).ok()?;
+        Some((dt, dt.offset()))
+    });
+});
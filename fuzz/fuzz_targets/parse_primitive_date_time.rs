@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use time_tests_fuzz::{check, is_primitive_date_time};
+
+fuzz_target!(|data: &[u8]| {
+    check(data, is_primitive_date_time, |parser, input| {
+        parser.parse_primitive_date_time(input).ok()
+    });
+});
@@ -0,0 +1,131 @@
+//! Checks shared by the fuzz targets, which run the same input through all four iterations.
+//!
+//! The iterations are meant to decode the same SQLite formats, but each one's format descriptions
+//! also accept some text the others do not. These leniency differences are expected:
+//!
+//! - `first`, `third` and `fourth` fall back to `well_known::Rfc3339` for offset date-times, so
+//!   they also accept a `Z` offset, a lowercase `t` or `z` and a leap second there. `second`
+//!   does not.
+//! - `third` and `fourth` make each punctuation mark after the minute optional, so they accept
+//!   `10:00:`, `10:0005` and `+05`.
+//! - `fourth` allows nothing, or a space and a `T`, between the date and the time.
+//!
+//! Text in the common grammar below is outside all of them, so every iteration must decode it to
+//! the same value or reject it. Outside it the iterations may disagree about whether text is
+//! valid, but any two that accept it must still agree on its value.
+//!
+//! ```text
+//! offset-date-time    = date separator clock offset
+//! primitive-date-time = date separator clock ["Z"]
+//! time                = clock
+//! date                = 4DIGIT "-" 2DIGIT "-" 2DIGIT
+//! separator           = " " / "T"
+//! clock               = 2DIGIT ":" 2DIGIT [":" 2DIGIT ["." 1*DIGIT]]  ; seconds not 60
+//! offset              = ("+" / "-") 2DIGIT ":" 2DIGIT
+//! ```
+
+use std::fmt::Debug;
+use time_tests::{First, Fourth, Second, SqliteTemporalParser, Third};
+
+const ITERATIONS: [(&str, &dyn SqliteTemporalParser); 4] = [
+    ("first", &First),
+    ("second", &Second),
+    ("third", &Third),
+    ("fourth", &Fourth),
+];
+
+/// Decodes `data` with every iteration and panics if two of them disagree in a way the
+/// leniency differences do not explain.
+pub fn check<T: PartialEq + Debug>(
+    data: &[u8],
+    in_common_grammar: fn(&[u8]) -> bool,
+    decode: impl Fn(&dyn SqliteTemporalParser, &str) -> Option<T>,
+) {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let results: Vec<_> = ITERATIONS
+        .iter()
+        .map(|&(name, parser)| (name, decode(parser, input)))
+        .collect();
+
+    for (i, (name, value)) in results.iter().enumerate() {
+        for (other_name, other_value) in &results[i + 1..] {
+            match (value, other_value) {
+                (Some(value), Some(other_value)) => assert_eq!(
+                    value, other_value,
+                    "{name} and {other_name} decode {input:?} differently"
+                ),
+                (Some(_), None) | (None, Some(_)) => assert!(
+                    !in_common_grammar(data),
+                    "only one of {name} and {other_name} accepts {input:?}"
+                ),
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+pub fn is_offset_date_time(input: &[u8]) -> bool {
+    date_time(input)
+        .and_then(offset)
+        .is_some_and(<[u8]>::is_empty)
+}
+
+pub fn is_primitive_date_time(input: &[u8]) -> bool {
+    date_time(input).is_some_and(|rest| rest.is_empty() || rest == b"Z")
+}
+
+pub fn is_time(input: &[u8]) -> bool {
+    clock(input).is_some_and(<[u8]>::is_empty)
+}
+
+pub fn is_date(input: &[u8]) -> bool {
+    date(input).is_some_and(<[u8]>::is_empty)
+}
+
+// Each of these reads one rule from the start of the input and returns the rest of it
+
+fn date_time(input: &[u8]) -> Option<&[u8]> {
+    let rest = date(input)?;
+    let rest = rest
+        .strip_prefix(b" ")
+        .or_else(|| rest.strip_prefix(b"T"))?;
+    clock(rest)
+}
+
+fn date(input: &[u8]) -> Option<&[u8]> {
+    let rest = digits(input, 4)?.strip_prefix(b"-")?;
+    let rest = digits(rest, 2)?.strip_prefix(b"-")?;
+    digits(rest, 2)
+}
+
+fn clock(input: &[u8]) -> Option<&[u8]> {
+    let rest = digits(input, 2)?.strip_prefix(b":")?;
+    let rest = digits(rest, 2)?;
+    let Some(seconds) = rest.strip_prefix(b":") else {
+        return Some(rest);
+    };
+    if seconds.starts_with(b"60") {
+        return None;
+    }
+    let rest = digits(seconds, 2)?;
+    let Some(fraction) = rest.strip_prefix(b".") else {
+        return Some(rest);
+    };
+    let count = fraction.iter().take_while(|b| b.is_ascii_digit()).count();
+    (count > 0).then(|| &fraction[count..])
+}
+
+fn offset(input: &[u8]) -> Option<&[u8]> {
+    let rest = input
+        .strip_prefix(b"+")
+        .or_else(|| input.strip_prefix(b"-"))?;
+    let rest = digits(rest, 2)?.strip_prefix(b":")?;
+    digits(rest, 2)
+}
+
+fn digits(input: &[u8], count: usize) -> Option<&[u8]> {
+    let (head, rest) = input.split_at_checked(count)?;
+    head.iter().all(u8::is_ascii_digit).then_some(rest)
+}
@@ -0,0 +1,431 @@
+//! Decoding columns that mix dates, times and date-times.
+
+use super::error::Attempt;
+use super::formats::*;
+use super::profile::{self, Canonical, Profile, Value, Zone, MAX_JULIAN_DAY};
+use super::walk::Walker;
+use super::{Candidate, Error, Problem};
+use time::format_description::FormatItem;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+/// The Unix timestamps SQLite's `auto` modifier accepts, from `-4713-11-24 12:00` to
+/// `9999-12-31 23:59:59`.
+const UNIX_EPOCH_RANGE: std::ops::RangeInclusive<f64> = -210_866_760_000.0..=253_402_300_799.0;
+
+const DATE_CANDIDATE: Candidate = Candidate {
+    name: "DATE",
+    items: DATE,
+};
+
+const DATE_TIME_CANDIDATE: Candidate = Candidate {
+    name: "OFFSET_DATE_TIME",
+    items: OFFSET_DATE_TIME,
+};
+
+const TIME_CANDIDATE: Candidate = Candidate {
+    name: "TIME_DESCRIPTION",
+    items: TIME_DESCRIPTION,
+};
+
+/// The part of `OFFSET_DATE_TIME` that follows the date, from the optional separators on.
+const OFFSET_DATE_TIME_AFTER_DATE: &[FormatItem<'_>] = OFFSET_DATE_TIME.split_at(DATE.len()).1;
+
+/// The separator and time that `DEFAULT_PRIMITIVE_DATE_TIME` adds to `DATE`.
+const DEFAULT_TIME_AFTER_DATE: &[FormatItem<'_>] =
+    DEFAULT_PRIMITIVE_DATE_TIME.split_at(DATE.len()).1;
+
+/// The offset that `DEFAULT_OFFSET_DATE_TIME` adds to `DEFAULT_PRIMITIVE_DATE_TIME`.
+const DEFAULT_OFFSET_AFTER_TIME: &[FormatItem<'_>] = DEFAULT_OFFSET_DATE_TIME
+    .split_at(DEFAULT_PRIMITIVE_DATE_TIME.len())
+    .1;
+
+/// A value decoded from a loosely typed SQLite column, as the most specific type the text
+/// supports.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SqliteTemporal {
+    Date(Date),
+    Time(Time),
+    PrimitiveDateTime(PrimitiveDateTime),
+    OffsetDateTime(OffsetDateTime),
+    /// A number in SQLite's Julian day range.
+    JulianDay(f64),
+    /// A number outside the Julian day range, read as seconds since the Unix epoch. Like SQLite,
+    /// this keeps any fraction of a second.
+    UnixEpoch(f64),
+}
+
+/// Decodes `input` without knowing up front which type it holds.
+///
+/// The text is walked once, date first, and the type is chosen by how far it gets: a date with
+/// nothing after it is a [`Date`], a date and time with an offset or `Z` suffix is an
+/// [`OffsetDateTime`] and one without is a [`PrimitiveDateTime`]. Text starting `HH:` is a
+/// [`Time`]. The date and time are read with the [`formats`](crate::formats) that
+/// [`fourth`](crate::fourth) uses, and values are not normalised.
+///
+/// Numbers are classified the way SQLite's `auto` modifier does it: values from `0` up to, but
+/// not including, `5373484.5` are Julian days, and other values from `-210866760000` to
+/// `253402300799` are Unix timestamps. Numbers outside both ranges are rejected.
+pub fn decode_any(input: &str) -> Result<SqliteTemporal, Error> {
+    let bytes = input.as_bytes();
+
+    if bytes.get(2) == Some(&b':') {
+        return time(input, bytes);
+    }
+    if !bytes.is_empty()
+        && bytes
+            .iter()
+            .all(|b| b.is_ascii_digit() || b"+-.".contains(b))
+    {
+        if let Ok(number) = input.parse() {
+            return auto(number).ok_or_else(|| {
+                let attempt = Attempt::at(
+                    input,
+                    "auto",
+                    0,
+                    Problem::OutOfRange {
+                        component: "number",
+                    },
+                );
+                Error::from_attempt(input, attempt)
+            });
+        }
+    }
+
+    let mut walker = Walker::new(bytes);
+    if walker.items(DATE) && walker.is_done() {
+        if let Ok(date) = Date::try_from(walker.parsed) {
+            return Ok(SqliteTemporal::Date(date));
+        }
+        return Err(Error::diagnose::<Date>(input, &[DATE_CANDIDATE]));
+    }
+
+    if walker.items(OFFSET_DATE_TIME_AFTER_DATE) {
+        let zulu = walker.remaining() == b"Z";
+        if walker.is_done() || zulu {
+            if let Ok(dt) = PrimitiveDateTime::try_from(walker.parsed) {
+                if zulu {
+                    return Ok(SqliteTemporal::OffsetDateTime(dt.assume_utc()));
+                }
+                if walker.start_of("offset hour").is_none() {
+                    return Ok(SqliteTemporal::PrimitiveDateTime(dt));
+                }
+                if let Ok(offset) = UtcOffset::try_from(walker.parsed) {
+                    return Ok(SqliteTemporal::OffsetDateTime(dt.assume_offset(offset)));
+                }
+            }
+        }
+    }
+
+    Err(Error::diagnose::<PrimitiveDateTime>(
+        input,
+        &[DATE_TIME_CANDIDATE],
+    ))
+}
+
+fn time(input: &str, bytes: &[u8]) -> Result<SqliteTemporal, Error> {
+    let mut walker = Walker::new(bytes);
+    if walker.items(TIME_DESCRIPTION) && walker.is_done() {
+        if let Ok(time) = Time::try_from(walker.parsed) {
+            return Ok(SqliteTemporal::Time(time));
+        }
+    }
+
+    Err(Error::diagnose::<Time>(input, &[TIME_CANDIDATE]))
+}
+
+/// Like [`decode_any`], but reading `input` with `profile`'s grammar.
+///
+/// The text is read once and the type is chosen by which fields it had, as for `decode_any`.
+/// Under the lenient profiles, values are normalised and moved to UTC as the profile describes,
+/// `now` is an offset date-time, and numbers are classified as `decode_any` does.
+/// [`Profile::Strict`] accepts only the canonical formats and no numbers.
+pub fn decode_any_with_profile(input: &str, profile: Profile) -> Result<SqliteTemporal, Error> {
+    if profile == Profile::Strict {
+        return strict(input);
+    }
+
+    let value = profile.scan(input)?;
+    if let Value::JulianDay(number) = value {
+        if let Some(number) = auto(number) {
+            return Ok(number);
+        }
+    }
+    // Rejects numbers outside both ranges
+    let (utc, offset) = profile.instant_of(input, &value)?;
+    let with_offset = || {
+        let utc = utc.assume_utc();
+        utc.checked_to_offset(offset).unwrap_or(utc)
+    };
+    Ok(match value {
+        Value::Text(fields) => match (fields.date, fields.clock, fields.zone) {
+            (_, None, _) => SqliteTemporal::Date(utc.date()),
+            (None, Some(_), _) => SqliteTemporal::Time(utc.time()),
+            (Some(_), Some(_), Zone::None) => SqliteTemporal::PrimitiveDateTime(utc),
+            (Some(_), Some(_), _) => SqliteTemporal::OffsetDateTime(with_offset()),
+        },
+        Value::JulianDay(day) => SqliteTemporal::JulianDay(day),
+        Value::Now => SqliteTemporal::OffsetDateTime(with_offset()),
+    })
+}
+
+/// Walks `input` once through the strict formats, each of which extends the one before: the
+/// date, then the separator and time, then the offset. A time on its own starts `HH:`.
+fn strict(input: &str) -> Result<SqliteTemporal, Error> {
+    let bytes = input.as_bytes();
+    let mut walker = Walker::new(bytes);
+
+    if bytes.get(2) == Some(&b':') {
+        let matched = walker.items(DEFAULT_TIME);
+        return decoded(input, matched, &walker).map(SqliteTemporal::Time);
+    }
+    if !walker.items(DATE) {
+        // Not even a date, so each format explains how far it got
+        return Err(profile::strict_error(input));
+    }
+    if walker.is_done() {
+        return decoded(input, true, &walker).map(SqliteTemporal::Date);
+    }
+    if !walker.items(DEFAULT_TIME_AFTER_DATE) {
+        let error = Error::diagnose::<PrimitiveDateTime>(input, PrimitiveDateTime::STRICT);
+        return Err(error.and(Error::diagnose::<OffsetDateTime>(
+            input,
+            OffsetDateTime::STRICT,
+        )));
+    }
+    if walker.is_done() {
+        return decoded(input, true, &walker).map(SqliteTemporal::PrimitiveDateTime);
+    }
+    let matched = walker.items(DEFAULT_OFFSET_AFTER_TIME);
+    decoded(input, matched, &walker).map(SqliteTemporal::OffsetDateTime)
+}
+
+/// The value a strict walk decoded, if every item `matched` and nothing is left over.
+fn decoded<T: Canonical>(input: &str, matched: bool, walker: &Walker<'_>) -> Result<T, Error> {
+    let value = (matched && walker.is_done())
+        .then(|| T::try_from(walker.parsed).ok())
+        .flatten();
+    value.ok_or_else(|| Error::diagnose::<T>(input, T::STRICT))
+}
+
+/// Classifies a number the way SQLite's `auto` modifier does.
+fn auto(number: f64) -> Option<SqliteTemporal> {
+    if (0.0..MAX_JULIAN_DAY).contains(&number) {
+        return Some(SqliteTemporal::JulianDay(number));
+    }
+
+    UNIX_EPOCH_RANGE
+        .contains(&number)
+        .then_some(SqliteTemporal::UnixEpoch(number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{date, datetime, time};
+
+    const SQLITE: Profile = Profile::SqliteCompatible;
+
+    type Decode = fn(&str) -> Result<SqliteTemporal, Error>;
+
+    #[test]
+    fn test_decode_any() {
+        assert_eq!(
+            decode_any("2016-03-07").unwrap(),
+            SqliteTemporal::Date(date!(2016 - 03 - 07))
+        );
+        assert_eq!(
+            decode_any("20:45:31.133").unwrap(),
+            SqliteTemporal::Time(time!(20:45:31.133))
+        );
+        assert_eq!(
+            decode_any("2014-08-27T00:05").unwrap(),
+            SqliteTemporal::PrimitiveDateTime(datetime!(2014-08-27 00:05))
+        );
+        assert_eq!(
+            decode_any("2018-12-01 04:09:19.543").unwrap(),
+            SqliteTemporal::PrimitiveDateTime(datetime!(2018-12-01 04:09:19.543))
+        );
+        assert_eq!(
+            decode_any("2016-03-07T22:36:55.135+03:30").unwrap(),
+            SqliteTemporal::OffsetDateTime(datetime!(2016-3-7 22:36:55.135+3:30))
+        );
+        assert_eq!(
+            decode_any("2013-09-17 23:59-01:00").unwrap(),
+            SqliteTemporal::OffsetDateTime(datetime!(2013-09-17 23:59-1))
+        );
+        assert_eq!(
+            decode_any("2012-06-25 22:03:13.321Z").unwrap(),
+            SqliteTemporal::OffsetDateTime(datetime!(2012-06-25 22:03:13.321 UTC))
+        );
+    }
+
+    #[test]
+    fn test_decode_any_with_profile() {
+        assert_eq!(
+            decode_any_with_profile("2016-03-07", SQLITE).unwrap(),
+            SqliteTemporal::Date(date!(2016 - 03 - 07))
+        );
+        assert_eq!(
+            decode_any_with_profile("20:45:31.133", SQLITE).unwrap(),
+            SqliteTemporal::Time(time!(20:45:31.133))
+        );
+        assert_eq!(
+            decode_any_with_profile("2014-08-27T00:05", SQLITE).unwrap(),
+            SqliteTemporal::PrimitiveDateTime(datetime!(2014-08-27 00:05))
+        );
+        assert_eq!(
+            decode_any_with_profile("2016-03-07T22:36:55.135+03:30", SQLITE).unwrap(),
+            SqliteTemporal::OffsetDateTime(datetime!(2016-3-7 22:36:55.135+3:30))
+        );
+        assert_eq!(
+            decode_any_with_profile("2012-06-25 22:03:13.321Z", SQLITE).unwrap(),
+            SqliteTemporal::OffsetDateTime(datetime!(2012-06-25 22:03:13.321 UTC))
+        );
+    }
+
+    #[test]
+    fn test_decode_any_normalises() {
+        assert_eq!(
+            decode_any_with_profile("2016-02-30", SQLITE).unwrap(),
+            SqliteTemporal::Date(date!(2016 - 03 - 01))
+        );
+        assert_eq!(
+            decode_any_with_profile("10:00+01:00", SQLITE).unwrap(),
+            SqliteTemporal::Time(time!(9:00))
+        );
+        assert_eq!(
+            decode_any_with_profile(" 2016-03-07t10:00", Profile::Permissive).unwrap(),
+            SqliteTemporal::PrimitiveDateTime(datetime!(2016-03-07 10:00))
+        );
+    }
+
+    #[test]
+    fn test_decode_any_numbers() {
+        let decoders: [Decode; 2] = [decode_any, |input| decode_any_with_profile(input, SQLITE)];
+        for decode in decoders {
+            assert_eq!(
+                decode("2457455.442304803").unwrap(),
+                SqliteTemporal::JulianDay(2457455.442304803)
+            );
+            assert_eq!(
+                decode("2451545").unwrap(),
+                SqliteTemporal::JulianDay(2451545.0)
+            );
+            assert_eq!(
+                decode("5373484.4999995").unwrap(),
+                SqliteTemporal::JulianDay(5373484.4999995)
+            );
+            assert_eq!(
+                decode("1457390215").unwrap(),
+                SqliteTemporal::UnixEpoch(1457390215.0)
+            );
+            assert_eq!(
+                decode("1457390215.5").unwrap(),
+                SqliteTemporal::UnixEpoch(1457390215.5)
+            );
+            assert_eq!(
+                decode("-62167219200").unwrap(),
+                SqliteTemporal::UnixEpoch(-62167219200.0)
+            );
+            // The ends of the range SQLite reads as Unix timestamps
+            assert_eq!(
+                decode("-210866760000").unwrap(),
+                SqliteTemporal::UnixEpoch(-210866760000.0)
+            );
+            assert_eq!(
+                decode("253402300799").unwrap(),
+                SqliteTemporal::UnixEpoch(253402300799.0)
+            );
+            assert!(decode("-210866760000.5").is_err());
+            assert!(decode("253402300800").is_err());
+        }
+
+        let error = decode_any("253402300800").unwrap_err();
+        assert_eq!(
+            error.furthest().unwrap().problem(),
+            &Problem::OutOfRange {
+                component: "number"
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_any_errors() {
+        let error = decode_any("2016-02-30").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "could not decode `2016-02-30`: day is out of range at byte 8 (DATE)"
+        );
+        let error = decode_any_with_profile("2016-02-30", Profile::Strict).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "could not decode `2016-02-30`: day is out of range at byte 8 (DATE)"
+        );
+
+        let error = decode_any("2013-09-17 23:59x").unwrap_err();
+        assert_eq!(error.furthest().unwrap().position(), 16);
+        let error = decode_any_with_profile("2013-09-17 23:59x", SQLITE).unwrap_err();
+        assert_eq!(error.furthest().unwrap().position(), 16);
+
+        assert!(decode_any("25:00").is_err());
+        assert!(decode_any("").is_err());
+        assert!(decode_any("20:45+01:00").is_err());
+        assert!(decode_any_with_profile("25:00", SQLITE).is_err());
+        assert!(decode_any_with_profile("", SQLITE).is_err());
+        assert!(decode_any_with_profile("20:45+01", SQLITE).is_err());
+        assert!(decode_any_with_profile("2016-03-07 10:00", Profile::Strict).is_err());
+    }
+
+    #[test]
+    fn test_decode_any_strict() {
+        let strict = Profile::Strict;
+        #[cfg(not(feature = "default-format-iso8601-t"))]
+        let (pdt, odt) = ("2018-12-01 04:09:19.543", "2014-10-18 00:00:38.697+01:00");
+        #[cfg(feature = "default-format-iso8601-t")]
+        let (pdt, odt) = ("2018-12-01T04:09:19.543", "2014-10-18T00:00:38.697+01:00");
+        assert_eq!(
+            decode_any_with_profile(pdt, strict).unwrap(),
+            SqliteTemporal::PrimitiveDateTime(datetime!(2018-12-01 04:09:19.543))
+        );
+        assert_eq!(
+            decode_any_with_profile(odt, strict).unwrap(),
+            SqliteTemporal::OffsetDateTime(datetime!(2014-10-18 00:00:38.697 +01:00))
+        );
+        assert_eq!(
+            decode_any_with_profile("+2016-03-07", strict).unwrap(),
+            SqliteTemporal::Date(date!(2016 - 03 - 07))
+        );
+        assert_eq!(
+            decode_any_with_profile("20:45:31.133", strict).unwrap(),
+            SqliteTemporal::Time(time!(20:45:31.133))
+        );
+
+        // Explained by the formats the text got as far as
+        let formats = |input: &str| {
+            let error = decode_any_with_profile(input, strict).unwrap_err();
+            let formats: Vec<_> = error.attempts().iter().map(|a| a.format()).collect();
+            formats
+        };
+        assert_eq!(formats("20:45"), ["DEFAULT_TIME"]);
+        assert_eq!(formats("20:45:31"), ["DEFAULT_TIME"]);
+        assert_eq!(
+            formats("2016-03-07 10:00Z"),
+            ["DEFAULT_PRIMITIVE_DATE_TIME", "DEFAULT_OFFSET_DATE_TIME"]
+        );
+        let zulu = format!("{pdt}Z");
+        assert_eq!(formats(&zulu), ["DEFAULT_OFFSET_DATE_TIME"]);
+        assert_eq!(formats("2457455.5").len(), 4);
+    }
+
+    #[test]
+    fn test_strict_formats_extend_each_other() {
+        assert_eq!(
+            [DATE, DEFAULT_TIME_AFTER_DATE].concat(),
+            DEFAULT_PRIMITIVE_DATE_TIME
+        );
+        assert_eq!(
+            [DEFAULT_PRIMITIVE_DATE_TIME, DEFAULT_OFFSET_AFTER_TIME].concat(),
+            DEFAULT_OFFSET_DATE_TIME
+        );
+    }
+}
@@ -0,0 +1,380 @@
+//! Extra formats tried after the built-in ones, for legacy rows SQLite never wrote.
+
+use super::matched::MatchedFormat;
+use super::profile::{Profile, Profiled};
+use super::{Error, FormatReportingParser, Fourth, SqliteTemporalParser};
+use std::fmt;
+use time::error::TryFromParsed;
+use time::format_description::OwnedFormatItem;
+use time::parsing::Parsed;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+type ParserFn = dyn Fn(&str) -> Option<Parsed> + Send + Sync;
+
+/// The [`fourth`](crate::fourth) iteration limited to a [`Profile`], followed by named fallbacks
+/// tried in the order they were added. The fallbacks are tried on text the profile rejects too.
+///
+/// A fallback is either an owned `time` format description or a closure that fills in a
+/// [`Parsed`]. Either way the fields are converted to whichever type is being decoded, so one
+/// fallback serves every decoder that its fields are enough for. A fallback whose fields would be
+/// dropped, like a date when decoding a `Time` or an offset when decoding a `PrimitiveDateTime`,
+/// does not match.
+///
+/// ```
+/// use time::format_description::parse_owned;
+/// use time_tests::chain::{ChainMatch, FallbackChain};
+/// use time_tests::profile::Profile;
+/// use time_tests::SqliteTemporalParser;
+///
+/// let chain = FallbackChain::new(Profile::SqliteCompatible).with_description(
+///     "dd/mm/yyyy",
+///     parse_owned::<2>("[day]/[month]/[year] [hour]:[minute]").unwrap(),
+/// );
+/// let (dt, matched) = chain
+///     .parse_primitive_date_time_with_format("07/03/2016 22:36")
+///     .unwrap();
+/// assert_eq!(dt, chain.parse_primitive_date_time("2016-03-07 22:36").unwrap());
+/// assert_eq!(matched, ChainMatch::Fallback("dd/mm/yyyy"));
+/// ```
+pub struct FallbackChain {
+    builtin: Profiled<Fourth>,
+    fallbacks: Vec<Fallback>,
+}
+
+struct Fallback {
+    name: &'static str,
+    kind: FallbackKind,
+}
+
+enum FallbackKind {
+    Description(OwnedFormatItem),
+    Parser(Box<ParserFn>),
+}
+
+/// Which part of a [`FallbackChain`] decoded a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainMatch {
+    /// One of the SQLite formats the chain's profile accepts, as described by [`MatchedFormat`].
+    Builtin(MatchedFormat),
+    /// The fallback registered under this name.
+    Fallback(&'static str),
+}
+
+impl FallbackChain {
+    /// A chain without fallbacks, whose built-in formats are those `profile` accepts.
+    pub fn new(profile: Profile) -> Self {
+        Self {
+            builtin: Fourth.with_profile(profile),
+            fallbacks: Vec::new(),
+        }
+    }
+
+    /// Appends a format description, which must match the whole input.
+    pub fn with_description(
+        mut self,
+        name: &'static str,
+        description: impl Into<OwnedFormatItem>,
+    ) -> Self {
+        self.fallbacks.push(Fallback {
+            name,
+            kind: FallbackKind::Description(description.into()),
+        });
+        self
+    }
+
+    /// Appends a closure, for formats a description cannot express. It returns `None` for input
+    /// it does not recognise.
+    pub fn with_parser(
+        mut self,
+        name: &'static str,
+        parser: impl Fn(&str) -> Option<Parsed> + Send + Sync + 'static,
+    ) -> Self {
+        self.fallbacks.push(Fallback {
+            name,
+            kind: FallbackKind::Parser(Box::new(parser)),
+        });
+        self
+    }
+
+    /// Like [`parse_offset_date_time`](SqliteTemporalParser::parse_offset_date_time), but also
+    /// reports what matched.
+    pub fn parse_offset_date_time_with_format(
+        &self,
+        input: &str,
+    ) -> Result<(OffsetDateTime, ChainMatch), Error> {
+        match self.builtin.parse_offset_date_time_with_format(input) {
+            Ok((dt, format)) => Ok((dt, ChainMatch::Builtin(format))),
+            Err(error) => self.fall_back(input, error),
+        }
+    }
+
+    /// Like [`parse_primitive_date_time`](SqliteTemporalParser::parse_primitive_date_time), but
+    /// also reports what matched.
+    pub fn parse_primitive_date_time_with_format(
+        &self,
+        input: &str,
+    ) -> Result<(PrimitiveDateTime, ChainMatch), Error> {
+        match self.builtin.parse_primitive_date_time_with_format(input) {
+            Ok((dt, format)) => Ok((dt, ChainMatch::Builtin(format))),
+            Err(error) => self.fall_back(input, error),
+        }
+    }
+
+    /// Like [`parse_time`](SqliteTemporalParser::parse_time), but also reports what matched.
+    pub fn parse_time_with_format(&self, input: &str) -> Result<(Time, ChainMatch), Error> {
+        match self.builtin.parse_time_with_format(input) {
+            Ok((time, format)) => Ok((time, ChainMatch::Builtin(format))),
+            Err(error) => self.fall_back(input, error),
+        }
+    }
+
+    /// Tries each fallback in turn, adding every fallback to the built-in formats' `error` if
+    /// none of them match.
+    fn fall_back<T: Decoded>(&self, input: &str, error: Error) -> Result<(T, ChainMatch), Error> {
+        self.fallbacks
+            .iter()
+            .find_map(|fallback| {
+                let value = fallback.parse(input)?;
+                Some((value, ChainMatch::Fallback(fallback.name)))
+            })
+            .ok_or_else(|| error.rejected_by(self.fallbacks.iter().map(|fallback| fallback.name)))
+    }
+}
+
+/// A type a fallback decodes to, with the kinds of field it keeps.
+trait Decoded: TryFrom<Parsed, Error = TryFromParsed> {
+    const DATE: bool;
+    const TIME: bool;
+    const OFFSET: bool;
+}
+
+impl Decoded for OffsetDateTime {
+    const DATE: bool = true;
+    const TIME: bool = true;
+    const OFFSET: bool = true;
+}
+
+impl Decoded for PrimitiveDateTime {
+    const DATE: bool = true;
+    const TIME: bool = true;
+    const OFFSET: bool = false;
+}
+
+impl Decoded for Time {
+    const DATE: bool = false;
+    const TIME: bool = true;
+    const OFFSET: bool = false;
+}
+
+impl Decoded for Date {
+    const DATE: bool = true;
+    const TIME: bool = false;
+    const OFFSET: bool = false;
+}
+
+fn has_date(parsed: &Parsed) -> bool {
+    parsed.year().is_some()
+        || parsed.year_last_two().is_some()
+        || parsed.iso_year().is_some()
+        || parsed.iso_year_last_two().is_some()
+        || parsed.month().is_some()
+        || parsed.sunday_week_number().is_some()
+        || parsed.monday_week_number().is_some()
+        || parsed.iso_week_number().is_some()
+        || parsed.weekday().is_some()
+        || parsed.ordinal().is_some()
+        || parsed.day().is_some()
+        || parsed.unix_timestamp_nanos().is_some()
+}
+
+fn has_time(parsed: &Parsed) -> bool {
+    parsed.hour_24().is_some()
+        || parsed.hour_12().is_some()
+        || parsed.hour_12_is_pm().is_some()
+        || parsed.minute().is_some()
+        || parsed.second().is_some()
+        || parsed.subsecond().is_some()
+        || parsed.unix_timestamp_nanos().is_some()
+}
+
+fn has_offset(parsed: &Parsed) -> bool {
+    parsed.offset_hour().is_some()
+        || parsed.offset_minute_signed().is_some()
+        || parsed.offset_second_signed().is_some()
+}
+
+impl Fallback {
+    fn parse<T: Decoded>(&self, input: &str) -> Option<T> {
+        let parsed = match &self.kind {
+            FallbackKind::Description(description) => {
+                let mut parsed = Parsed::new();
+                let remaining = parsed.parse_item(input.as_bytes(), description).ok()?;
+                if !remaining.is_empty() {
+                    return None;
+                }
+                parsed
+            }
+            FallbackKind::Parser(parser) => parser(input)?,
+        };
+
+        if (has_date(&parsed) && !T::DATE)
+            || (has_time(&parsed) && !T::TIME)
+            || (has_offset(&parsed) && !T::OFFSET)
+        {
+            return None;
+        }
+        T::try_from(parsed).ok()
+    }
+}
+
+impl SqliteTemporalParser for FallbackChain {
+    fn parse_offset_date_time(&self, input: &str) -> Result<OffsetDateTime, Error> {
+        match self.builtin.parse_offset_date_time(input) {
+            Ok(dt) => Ok(dt),
+            Err(error) => self.fall_back(input, error).map(|(dt, _)| dt),
+        }
+    }
+
+    fn parse_primitive_date_time(&self, input: &str) -> Result<PrimitiveDateTime, Error> {
+        match self.builtin.parse_primitive_date_time(input) {
+            Ok(dt) => Ok(dt),
+            Err(error) => self.fall_back(input, error).map(|(dt, _)| dt),
+        }
+    }
+
+    fn parse_time(&self, input: &str) -> Result<Time, Error> {
+        match self.builtin.parse_time(input) {
+            Ok(time) => Ok(time),
+            Err(error) => self.fall_back(input, error).map(|(time, _)| time),
+        }
+    }
+
+    fn parse_date(&self, input: &str) -> Result<Date, Error> {
+        match self.builtin.parse_date(input) {
+            Ok(date) => Ok(date),
+            Err(error) => self.fall_back(input, error).map(|(date, _)| date),
+        }
+    }
+}
+
+impl fmt::Debug for FallbackChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.fallbacks.iter().map(|fallback| fallback.name))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matched::{Precision, Separator};
+    use crate::{Attempt, Problem};
+    use time::format_description::parse_owned;
+    use time::macros::datetime;
+    use time::Month;
+
+    /// `YYYYMMDDTHHMMSS`, split by hand.
+    fn compact(input: &str) -> Option<Parsed> {
+        let bytes = input.as_bytes();
+        if bytes.len() != 15 || bytes[8] != b'T' {
+            return None;
+        }
+        let number = |range: std::ops::Range<usize>| input.get(range)?.parse::<u16>().ok();
+
+        Parsed::new()
+            .with_year(number(0..4)?.into())?
+            .with_month(Month::try_from(number(4..6)? as u8).ok()?)?
+            .with_day((number(6..8)? as u8).try_into().ok()?)?
+            .with_hour_24(number(9..11)? as u8)?
+            .with_minute(number(11..13)? as u8)?
+            .with_second(number(13..15)? as u8)
+    }
+
+    fn chain() -> FallbackChain {
+        FallbackChain::new(Profile::Permissive)
+            .with_description(
+                "dd/mm/yyyy",
+                parse_owned::<2>("[day]/[month]/[year] [hour]:[minute]").unwrap(),
+            )
+            .with_parser("compact", compact)
+    }
+
+    #[test]
+    fn test_builtin_first() {
+        let (dt, matched) = chain()
+            .parse_primitive_date_time_with_format("2016-10-29T02:07:17")
+            .unwrap();
+        assert_eq!(dt, datetime!(2016-10-29 02:07:17));
+        assert_eq!(
+            matched,
+            ChainMatch::Builtin(MatchedFormat {
+                separator: Some(Separator::T),
+                precision: Precision::Seconds,
+                offset: crate::matched::OffsetStyle::None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_fallbacks() {
+        let chain = chain();
+        assert_eq!(
+            chain
+                .parse_primitive_date_time_with_format("07/03/2016 22:36")
+                .unwrap(),
+            (
+                datetime!(2016-03-07 22:36),
+                ChainMatch::Fallback("dd/mm/yyyy")
+            )
+        );
+        assert_eq!(
+            chain
+                .parse_primitive_date_time_with_format("20160307T223655")
+                .unwrap(),
+            (
+                datetime!(2016-03-07 22:36:55),
+                ChainMatch::Fallback("compact")
+            )
+        );
+        // Neither fallback has an offset, and a date or a time alone would drop fields
+        assert!(chain.parse_offset_date_time("20160307T223655").is_err());
+        assert!(chain.parse_time("20160307T223655").is_err());
+        assert!(chain.parse_date("20160307T223655").is_err());
+    }
+
+    #[test]
+    fn test_profile() {
+        let strict = FallbackChain::new(Profile::Strict).with_parser("compact", compact);
+        let canonical = crate::encode::pdt_default(datetime!(2016-10-29 02:07:17)).unwrap();
+        assert!(matches!(
+            strict.parse_primitive_date_time_with_format(&canonical),
+            Ok((_, ChainMatch::Builtin(_)))
+        ));
+        // `fourth` reads this, but the profile does not, and neither does the fallback
+        let error = strict
+            .parse_primitive_date_time("2016-10-29 02:07")
+            .unwrap_err();
+        let formats: Vec<_> = error.attempts().iter().map(Attempt::format).collect();
+        assert_eq!(formats, ["DEFAULT_PRIMITIVE_DATE_TIME", "compact"]);
+        assert_eq!(
+            strict.parse_primitive_date_time("20161029T020717").unwrap(),
+            datetime!(2016-10-29 02:07:17)
+        );
+    }
+
+    #[test]
+    fn test_fallback_errors() {
+        let error = chain()
+            .parse_primitive_date_time("07/03/2016 22:36:55")
+            .unwrap_err();
+        let rejected: Vec<_> = error
+            .attempts()
+            .iter()
+            .filter(|attempt| attempt.problem() == &Problem::Rejected)
+            .map(|attempt| attempt.format())
+            .collect();
+        assert_eq!(rejected, ["dd/mm/yyyy", "compact"]);
+        assert_eq!(format!("{:?}", chain()), r#"["dd/mm/yyyy", "compact"]"#);
+    }
+}
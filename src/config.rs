@@ -0,0 +1,465 @@
+//! Parsers built from a description of which SQLite formats a column may hold.
+
+use super::formats::*;
+use super::matched::Separator;
+use super::profile::Profile;
+use super::{Candidate, Error, SqliteTemporalParser};
+use time::format_description::FormatItem::{self, Compound, Literal, Optional};
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+/// Which separators, optional fields and suffixes a [`ConfiguredParser`] accepts.
+///
+/// The default accepts the formats SQLite documents: a space or `T` separator, optional seconds
+/// and fractional seconds, a trailing `Z`, and offsets written `+HH:MM`. This is not quite what
+/// [`Fourth`](crate::Fourth) accepts, which also reads `+HH` and `+HHMM` offsets but reads an
+/// offset date-time ending in `Z` only after a `T`.
+///
+/// A parser is also limited to a [`Profile`], [`Permissive`](Profile::Permissive) by default, which
+/// narrows the text it accepts further. Values are always decoded by the configured formats. A
+/// configuration made from a profile with `ParserConfig::from` has the default formats otherwise.
+///
+/// ```
+/// use time_tests::config::ParserConfig;
+/// use time_tests::matched::Separator;
+/// use time_tests::SqliteTemporalParser;
+///
+/// let parser = ParserConfig::new()
+///     .separators([Separator::Space])
+///     .require_seconds(true)
+///     .build();
+/// assert!(parser.parse_primitive_date_time("2019-01-02 05:10:20").is_ok());
+/// assert!(parser.parse_primitive_date_time("2019-01-02T05:10:20").is_err());
+/// assert!(parser.parse_primitive_date_time("2019-01-02 05:10").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParserConfig {
+    separators: Vec<Separator>,
+    require_seconds: bool,
+    allow_subseconds: bool,
+    allow_zulu: bool,
+    offsets: Vec<OffsetForm>,
+    profile: Profile,
+}
+
+/// A way of writing a numeric offset from UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OffsetForm {
+    /// `+HH:MM`, the only form SQLite reads.
+    WithColon,
+    /// `+HHMM`
+    WithoutColon,
+    /// `+HH`
+    HourOnly,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            separators: vec![Separator::Space, Separator::T],
+            require_seconds: false,
+            allow_subseconds: true,
+            allow_zulu: true,
+            offsets: vec![OffsetForm::WithColon],
+            profile: Profile::Permissive,
+        }
+    }
+}
+
+impl From<Profile> for ParserConfig {
+    fn from(profile: Profile) -> Self {
+        Self::new().profile(profile)
+    }
+}
+
+impl ParserConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The separators allowed between the date and the time. They are tried in the order given,
+    /// so the most common one should come first.
+    pub fn separators(mut self, separators: impl IntoIterator<Item = Separator>) -> Self {
+        self.separators = separators.into_iter().collect();
+        self
+    }
+
+    /// Whether times must include seconds, rejecting `HH:MM`.
+    pub fn require_seconds(mut self, require: bool) -> Self {
+        self.require_seconds = require;
+        self
+    }
+
+    /// Whether seconds may have a fractional part.
+    pub fn allow_subseconds(mut self, allow: bool) -> Self {
+        self.allow_subseconds = allow;
+        self
+    }
+
+    /// Whether date-times may end in `Z`. An offset date-time read this way is in UTC, and a
+    /// primitive date-time ignores it.
+    pub fn allow_zulu(mut self, allow: bool) -> Self {
+        self.allow_zulu = allow;
+        self
+    }
+
+    /// How offset date-times may write their offset. With none, only a `Z` is read, if
+    /// [`allow_zulu`](Self::allow_zulu) allows it.
+    pub fn offsets(mut self, offsets: impl IntoIterator<Item = OffsetForm>) -> Self {
+        self.offsets = offsets.into_iter().collect();
+        self
+    }
+
+    /// The profile text must also be in. Text only the profile accepts is still rejected, and the
+    /// profile does not change the value decoded.
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Compiles the configuration into one description per separator and suffix.
+    pub fn build(&self) -> ConfiguredParser {
+        let mut offset_date_time = Vec::new();
+        let mut primitive_date_time = Vec::new();
+        for &separator in &self.separators {
+            let date_time = self.date_time(separator);
+
+            if let Some(offset) = self.offset() {
+                let mut with_offset = date_time.clone();
+                with_offset.push(offset);
+                offset_date_time.push(Description::new(name(separator, false), with_offset));
+            }
+
+            if self.allow_zulu {
+                let mut zulu = date_time.clone();
+                zulu.push(Literal(b"Z"));
+                primitive_date_time.push(Description::new(name(separator, false), date_time));
+                primitive_date_time.push(Description::new(name(separator, true), zulu.clone()));
+                offset_date_time.push(Description {
+                    utc: true,
+                    ..Description::new(name(separator, true), zulu)
+                });
+            } else {
+                primitive_date_time.push(Description::new(name(separator, false), date_time));
+            }
+        }
+
+        let mut time = vec![HOUR_MINUTE];
+        time.push(self.seconds());
+
+        ConfiguredParser {
+            offset_date_time,
+            primitive_date_time,
+            time: Description::new("time", time),
+            profile: self.profile,
+        }
+    }
+
+    fn date_time(&self, separator: Separator) -> Vec<FormatItem<'static>> {
+        let mut items = DATE.to_vec();
+        match separator {
+            Separator::Space => items.push(Literal(b" ")),
+            Separator::T => items.push(Literal(b"T")),
+            Separator::None => {}
+            Separator::SpaceAndT => items.push(Literal(b" T")),
+        }
+        items.push(HOUR_MINUTE);
+        items.push(self.seconds());
+        items
+    }
+
+    /// One item reading any of the allowed offset forms, each exactly as written.
+    fn offset(&self) -> Option<FormatItem<'static>> {
+        let allows = |form| self.offsets.contains(&form);
+        let forms = (
+            allows(OffsetForm::WithColon),
+            allows(OffsetForm::WithoutColon),
+            allows(OffsetForm::HourOnly),
+        );
+        Some(match forms {
+            (true, false, false) => NUMERIC_OFFSET,
+            (false, true, false) => Compound(&[OFFSET_HOUR, OFFSET_MINUTE]),
+            (false, false, true) => OFFSET_HOUR,
+            (true, true, false) => {
+                Compound(&[OFFSET_HOUR, Optional(&Literal(b":")), OFFSET_MINUTE])
+            }
+            (true, false, true) => Compound(&[
+                OFFSET_HOUR,
+                Optional(&Compound(&[Literal(b":"), OFFSET_MINUTE])),
+            ]),
+            (false, true, true) => Compound(&[OFFSET_HOUR, Optional(&OFFSET_MINUTE)]),
+            (true, true, true) => Compound(&[
+                OFFSET_HOUR,
+                Optional(&Compound(&[Optional(&Literal(b":")), OFFSET_MINUTE])),
+            ]),
+            (false, false, false) => return None,
+        })
+    }
+
+    fn seconds(&self) -> FormatItem<'static> {
+        match (self.require_seconds, self.allow_subseconds) {
+            (true, true) => SECONDS_AND_FRACTION,
+            (true, false) => SECONDS,
+            (false, true) => Optional(&SECONDS_AND_FRACTION),
+            (false, false) => Optional(&SECONDS),
+        }
+    }
+}
+
+/// A parser compiled from a [`ParserConfig`].
+#[derive(Debug, Clone)]
+pub struct ConfiguredParser {
+    offset_date_time: Vec<Description>,
+    primitive_date_time: Vec<Description>,
+    time: Description,
+    profile: Profile,
+}
+
+#[derive(Debug, Clone)]
+struct Description {
+    name: &'static str,
+    items: Vec<FormatItem<'static>>,
+    /// Whether the description ends in `Z` rather than an offset.
+    utc: bool,
+}
+
+impl Description {
+    fn new(name: &'static str, items: Vec<FormatItem<'static>>) -> Self {
+        Self {
+            name,
+            items,
+            utc: false,
+        }
+    }
+
+    fn candidate(&self) -> Candidate<'_> {
+        Candidate {
+            name: self.name,
+            items: &self.items,
+        }
+    }
+}
+
+fn name(separator: Separator, zulu: bool) -> &'static str {
+    match (separator, zulu) {
+        (Separator::Space, false) => "space separated",
+        (Separator::Space, true) => "space separated with Z",
+        (Separator::T, false) => "T separated",
+        (Separator::T, true) => "T separated with Z",
+        (Separator::None, false) => "unseparated",
+        (Separator::None, true) => "unseparated with Z",
+        (Separator::SpaceAndT, false) => "space and T separated",
+        (Separator::SpaceAndT, true) => "space and T separated with Z",
+    }
+}
+
+fn candidates(descriptions: &[Description]) -> Vec<Candidate<'_>> {
+    descriptions.iter().map(Description::candidate).collect()
+}
+
+impl SqliteTemporalParser for ConfiguredParser {
+    fn parse_offset_date_time(&self, input: &str) -> Result<OffsetDateTime, Error> {
+        for description in &self.offset_date_time {
+            let decoded = if description.utc {
+                PrimitiveDateTime::parse(input, &description.items).map(|dt| dt.assume_utc())
+            } else {
+                OffsetDateTime::parse(input, &description.items)
+            };
+            if let Ok(dt) = decoded {
+                self.profile.admit::<OffsetDateTime>(input)?;
+                return Ok(dt);
+            }
+        }
+
+        Err(Error::diagnose::<OffsetDateTime>(
+            input,
+            &candidates(&self.offset_date_time),
+        ))
+    }
+
+    fn parse_primitive_date_time(&self, input: &str) -> Result<PrimitiveDateTime, Error> {
+        for description in &self.primitive_date_time {
+            if let Ok(dt) = PrimitiveDateTime::parse(input, &description.items) {
+                self.profile.admit::<PrimitiveDateTime>(input)?;
+                return Ok(dt);
+            }
+        }
+
+        Err(Error::diagnose::<PrimitiveDateTime>(
+            input,
+            &candidates(&self.primitive_date_time),
+        ))
+    }
+
+    fn parse_time(&self, input: &str) -> Result<Time, Error> {
+        let time = Time::parse(input, &self.time.items)
+            .map_err(|_| Error::diagnose::<Time>(input, &[self.time.candidate()]))?;
+        self.profile.admit::<Time>(input)?;
+        Ok(time)
+    }
+
+    fn parse_date(&self, input: &str) -> Result<Date, Error> {
+        let date = Date::parse(input, DATE).map_err(|_| {
+            Error::diagnose::<Date>(
+                input,
+                &[Candidate {
+                    name: "DATE",
+                    items: DATE,
+                }],
+            )
+        })?;
+        self.profile.admit::<Date>(input)?;
+        Ok(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Fourth;
+    use time::macros::{datetime, time};
+
+    #[test]
+    fn test_default_config() {
+        let parser = ParserConfig::new().build();
+        assert_eq!(
+            parser
+                .parse_offset_date_time("2016-03-07T22:36:55.135+03:30")
+                .unwrap(),
+            datetime!(2016-3-7 22:36:55.135+3:30)
+        );
+        assert_eq!(
+            parser
+                .parse_offset_date_time("2012-06-25 22:03:13.321Z")
+                .unwrap(),
+            datetime!(2012-06-25 22:03:13.321 UTC)
+        );
+        assert_eq!(
+            parser
+                .parse_primitive_date_time("2011-05-24 21:02Z")
+                .unwrap(),
+            datetime!(2011-05-24 21:02)
+        );
+        assert_eq!(parser.parse_time("19:44").unwrap(), time!(19:44));
+        assert!(parser
+            .parse_offset_date_time("2016-03-0722:36+05:00")
+            .is_err());
+        // Where the default differs from `Fourth`
+        let zulu = "2012-06-25 22:03:13.321Z";
+        assert!(parser.parse_offset_date_time(zulu).is_ok());
+        assert!(Fourth.parse_offset_date_time(zulu).is_err());
+        let no_colon = "2016-03-07 22:36+0530";
+        assert!(parser.parse_offset_date_time(no_colon).is_err());
+        assert!(Fourth.parse_offset_date_time(no_colon).is_ok());
+    }
+
+    #[test]
+    fn test_separators() {
+        let parser = ParserConfig::new().separators([Separator::Space]).build();
+        assert!(parser.parse_primitive_date_time("2017-11-30 03:08").is_ok());
+        assert!(parser
+            .parse_primitive_date_time("2014-08-27T00:05")
+            .is_err());
+
+        let parser = ParserConfig::new()
+            .separators([Separator::None, Separator::SpaceAndT])
+            .build();
+        assert!(parser.parse_primitive_date_time("2017-11-3003:08").is_ok());
+        assert!(parser
+            .parse_primitive_date_time("2017-11-30 T03:08")
+            .is_ok());
+        assert!(parser
+            .parse_primitive_date_time("2017-11-30 03:08")
+            .is_err());
+    }
+
+    #[test]
+    fn test_profile() {
+        let parser = ParserConfig::from(Profile::SqliteCompatible)
+            .offsets([OffsetForm::WithColon, OffsetForm::WithoutColon])
+            .build();
+        // Decoded by the configured formats, not rounded to the millisecond as SQLite does
+        assert_eq!(
+            parser.parse_time("20:45:31.1339").unwrap(),
+            time!(20:45:31.1339)
+        );
+        // Allowed by the configuration, but not by SQLite
+        assert!(parser
+            .parse_offset_date_time("2016-03-07 22:36+0530")
+            .is_err());
+        // In range for SQLite, but not in the configured formats
+        assert!(parser
+            .parse_primitive_date_time("2016-02-30 10:00")
+            .is_err());
+
+        let parser = ParserConfig::new().profile(Profile::Strict).build();
+        assert!(parser.parse_time("19:44").is_err());
+        assert!(parser.parse_date("2016-03-07").is_ok());
+    }
+
+    #[test]
+    fn test_offsets() {
+        let parser = ParserConfig::new()
+            .offsets([OffsetForm::WithoutColon, OffsetForm::HourOnly])
+            .build();
+        assert_eq!(
+            parser
+                .parse_offset_date_time("2016-03-07 22:36+0530")
+                .unwrap(),
+            datetime!(2016-3-7 22:36+5:30)
+        );
+        assert_eq!(
+            parser
+                .parse_offset_date_time("2016-03-07 22:36-05")
+                .unwrap(),
+            datetime!(2016-3-7 22:36-5)
+        );
+        assert!(parser
+            .parse_offset_date_time("2016-03-07 22:36+05:30")
+            .is_err());
+
+        let parser = ParserConfig::new()
+            .offsets([OffsetForm::WithColon, OffsetForm::HourOnly])
+            .build();
+        assert!(parser
+            .parse_offset_date_time("2016-03-07 22:36+05:30")
+            .is_ok());
+        assert!(parser.parse_offset_date_time("2016-03-07 22:36+05").is_ok());
+        assert!(parser
+            .parse_offset_date_time("2016-03-07 22:36+0530")
+            .is_err());
+        assert!(parser
+            .parse_offset_date_time("2016-03-07 22:36+05:")
+            .is_err());
+
+        // Without numeric offsets, only `Z` is left
+        let parser = ParserConfig::new().offsets([]).build();
+        assert!(parser
+            .parse_offset_date_time("2016-03-07 22:36+05:30")
+            .is_err());
+        assert!(parser.parse_offset_date_time("2016-03-07 22:36Z").is_ok());
+    }
+
+    #[test]
+    fn test_fields_and_suffixes() {
+        let parser = ParserConfig::new()
+            .require_seconds(true)
+            .allow_subseconds(false)
+            .allow_zulu(false)
+            .build();
+        assert!(parser.parse_time("21:46:32").is_ok());
+        assert!(parser.parse_time("19:44").is_err());
+        assert!(parser.parse_time("20:45:31.133").is_err());
+        assert!(parser
+            .parse_primitive_date_time("2013-07-26 23:04:14Z")
+            .is_err());
+        assert!(parser
+            .parse_offset_date_time("2016-03-07T22:36:55Z")
+            .is_err());
+
+        let error = parser.parse_time("19:44").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "could not decode `19:44`: expected `:` after minute at byte 5 (time)"
+        );
+    }
+}
@@ -0,0 +1,396 @@
+use super::formats::*;
+use std::fmt;
+use time::error::Format;
+use time::{OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+/// The Unix epoch as SQLite's internal Julian day number in milliseconds.
+const UNIX_EPOCH_JULIAN_DAY_MILLIS: i64 = 210_866_760_000_000;
+
+/// Encodes `date_time` in the canonical format chosen by the `default-format-*` features.
+pub fn odt_default(date_time: OffsetDateTime) -> Result<String, Format> {
+    date_time.format(DEFAULT_OFFSET_DATE_TIME)
+}
+
+/// Encodes `date_time` in the canonical format chosen by the `default-format-*` features.
+pub fn pdt_default(date_time: PrimitiveDateTime) -> Result<String, Format> {
+    date_time.format(DEFAULT_PRIMITIVE_DATE_TIME)
+}
+
+/// Encodes `time` in the canonical format chosen by the `default-format-*` features.
+pub fn time_default(time: Time) -> Result<String, Format> {
+    time.format(DEFAULT_TIME)
+}
+
+/// Encodes `date_time` as UTC text that SQLite can order bytewise.
+///
+/// Every value is written as `YYYY-MM-DD HH:MM:SS.fffffffff+00:00`, so all encoded values have the
+/// same width and the same offset, and comparing them as TEXT gives the same answer as comparing
+/// the instants they represent. Years outside `0000`-`9999` (after normalising to UTC) cannot be
+/// written at a fixed width and are rejected.
+pub fn odt_sortable(date_time: OffsetDateTime) -> Result<String, Format> {
+    let utc = date_time
+        .checked_to_offset(UtcOffset::UTC)
+        .ok_or(Format::InvalidComponent("year"))?;
+    if !(0..=9999).contains(&utc.year()) {
+        return Err(Format::InvalidComponent("year"));
+    }
+
+    utc.format(SORTABLE_UTC)
+}
+
+/// Encodes `date_time` as the REAL value SQLite's `julianday()` returns for the same instant.
+pub fn odt_julian_day(date_time: OffsetDateTime) -> f64 {
+    odt_julian_day_millis(date_time) as f64 / 86_400_000.0
+}
+
+/// Encodes `date_time`, taken to be UTC as SQLite does, as the REAL value `julianday()` returns.
+pub fn pdt_julian_day(date_time: PrimitiveDateTime) -> f64 {
+    pdt_julian_day_millis(date_time) as f64 / 86_400_000.0
+}
+
+/// Encodes `date_time` as the INTEGER value SQLite's `unixepoch()` returns for the same instant.
+pub fn odt_unix_epoch(date_time: OffsetDateTime) -> i64 {
+    odt_julian_day_millis(date_time) / 1000 - UNIX_EPOCH_JULIAN_DAY_MILLIS / 1000
+}
+
+/// Encodes `date_time`, taken to be UTC as SQLite does, as the INTEGER value `unixepoch()` returns.
+pub fn pdt_unix_epoch(date_time: PrimitiveDateTime) -> i64 {
+    pdt_julian_day_millis(date_time) / 1000 - UNIX_EPOCH_JULIAN_DAY_MILLIS / 1000
+}
+
+fn odt_julian_day_millis(date_time: OffsetDateTime) -> i64 {
+    let local = PrimitiveDateTime::new(date_time.date(), date_time.time());
+    pdt_julian_day_millis(local) - date_time.offset().whole_seconds() as i64 * 1000
+}
+
+/// Mirrors `computeJD` in SQLite's `date.c`, which keeps time as integer milliseconds since the
+/// Julian epoch. Fractional seconds are clamped to `.999` and then rounded half up to the
+/// millisecond, using the same floating point arithmetic SQLite does. SQLite reads the fraction as
+/// its digits divided by a power of ten; both are exact for nine digits, so `nanos / 1e9` is the
+/// same double.
+fn pdt_julian_day_millis(date_time: PrimitiveDateTime) -> i64 {
+    let mut fraction = date_time.nanosecond() as f64 / 1_000_000_000.0;
+    if fraction > 0.999 {
+        fraction = 0.999;
+    }
+    let seconds = date_time.second() as f64 + fraction;
+
+    date_time.to_julian_day() as i64 * 86_400_000 - 43_200_000
+        + date_time.hour() as i64 * 3_600_000
+        + date_time.minute() as i64 * 60_000
+        + (seconds * 1000.0 + 0.5) as i64
+}
+
+/// The reason a set of stored values would not sort chronologically as SQLite TEXT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderHazard {
+    /// The value at `index` could not be decoded as an `OffsetDateTime` or a `PrimitiveDateTime`.
+    Undecodable { index: usize },
+    /// The value at `index` has a sign in front of its year.
+    SignedYear { index: usize },
+    /// The value at `index` has different field widths or separators than the first value.
+    MixedLayout { index: usize },
+    /// The value at `index` has a different offset than the first value, or only one of them has
+    /// an offset.
+    MixedOffset { index: usize },
+}
+
+impl fmt::Display for OrderHazard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Undecodable { index } => write!(f, "value {index} is not a date and time"),
+            Self::SignedYear { index } => write!(f, "value {index} has a signed year"),
+            Self::MixedLayout { index } => {
+                write!(f, "value {index} is laid out differently from value 0")
+            }
+            Self::MixedOffset { index } => {
+                write!(f, "value {index} has a different offset from value 0")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OrderHazard {}
+
+/// Checks whether the TEXT values of an existing column sort in chronological order.
+///
+/// Values are order-safe when they all decode, with or without an offset, share one offset (or
+/// all have none) and share one layout (the same characters in the same places once digits are
+/// ignored). A trailing `Z` counts as the offset `+00:00`. The first value that breaks this is
+/// reported.
+pub fn check_order_safe<'a, I>(values: I) -> Result<(), OrderHazard>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut first: Option<(&str, Option<UtcOffset>)> = None;
+
+    for (index, value) in values.into_iter().enumerate() {
+        let offset = match super::fourth::odt_iteration(value) {
+            Ok(date_time) => Some(date_time.offset()),
+            Err(_) => {
+                super::fourth::pdt_iteration(value)
+                    .map_err(|_| OrderHazard::Undecodable { index })?;
+                value.ends_with(['Z', 'z']).then_some(UtcOffset::UTC)
+            }
+        };
+        if !value.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(OrderHazard::SignedYear { index });
+        }
+
+        match first {
+            None => first = Some((value, offset)),
+            Some((layout, first_offset)) => {
+                // Checked first, as a sign that flips changes the layout as well
+                if offset != first_offset {
+                    return Err(OrderHazard::MixedOffset { index });
+                }
+                if !same_layout(layout, value) {
+                    return Err(OrderHazard::MixedLayout { index });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn same_layout(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .all(|(a, b)| a == b || (a.is_ascii_digit() && b.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::RngExt;
+    use proptest::test_runner::{RngAlgorithm, TestRng};
+    use time::macros::datetime;
+    use time::Duration;
+
+    /// A random instant from year 1 to 9999, with an offset of up to 18 hours.
+    fn date_time(rng: &mut TestRng) -> OffsetDateTime {
+        let seconds = rng.random_range(0..253_402_300_799);
+        let nanoseconds = rng.random_range(0..1_000_000_000);
+        let offset_minutes = rng.random_range(-18 * 60..18 * 60);
+        let offset = UtcOffset::from_whole_seconds(offset_minutes * 60).unwrap();
+
+        (OffsetDateTime::UNIX_EPOCH - Duration::seconds(62_135_596_800)
+            + Duration::new(seconds, nanoseconds))
+        .to_offset(offset)
+    }
+
+    #[cfg(not(feature = "default-format-iso8601-t"))]
+    const SEPARATOR: char = ' ';
+    #[cfg(feature = "default-format-iso8601-t")]
+    const SEPARATOR: char = 'T';
+
+    #[cfg(not(feature = "default-format-millis"))]
+    const SUBSECOND: &str = ".5";
+    #[cfg(feature = "default-format-millis")]
+    const SUBSECOND: &str = ".500";
+
+    #[test]
+    fn test_default_format() {
+        let odt = datetime!(2016-3-7 22:36:55.5+3:30);
+        let encoded = odt_default(odt).unwrap();
+        assert_eq!(
+            encoded,
+            format!("2016-03-07{SEPARATOR}22:36:55{SUBSECOND}+03:30")
+        );
+        assert_eq!(super::super::third::odt_iteration(&encoded).unwrap(), odt);
+        assert_eq!(super::super::fourth::odt_iteration(&encoded).unwrap(), odt);
+        // Whichever separator the features choose, the ODT fast path is the canonical format
+        for candidates in [
+            super::super::third::ODT_CANDIDATES,
+            super::super::fourth::ODT_CANDIDATES,
+        ] {
+            assert_eq!(candidates[0].name, "DEFAULT_OFFSET_DATE_TIME");
+        }
+
+        let pdt = datetime!(2018-12-01 04:09:19.5);
+        let encoded = pdt_default(pdt).unwrap();
+        assert_eq!(encoded, format!("2018-12-01{SEPARATOR}04:09:19{SUBSECOND}"));
+        assert_eq!(super::super::third::pdt_iteration(&encoded).unwrap(), pdt);
+        assert_eq!(super::super::fourth::pdt_iteration(&encoded).unwrap(), pdt);
+
+        let time = pdt.time();
+        let encoded = time_default(time).unwrap();
+        assert_eq!(encoded, format!("04:09:19{SUBSECOND}"));
+        assert_eq!(super::super::third::time_iteration(&encoded).unwrap(), time);
+    }
+
+    #[test]
+    fn test_odt_sortable() {
+        assert_eq!(
+            odt_sortable(datetime!(2016-3-7 22:36:55.135+3:30)).unwrap(),
+            "2016-03-07 19:06:55.135000000+00:00"
+        );
+        assert_eq!(
+            odt_sortable(datetime!(0000-01-01 00:00 UTC)).unwrap(),
+            "0000-01-01 00:00:00.000000000+00:00"
+        );
+        assert!(odt_sortable(datetime!(0000-01-01 00:30+1)).is_err());
+        assert!(odt_sortable(datetime!(9999-12-31 23:30-1)).is_err());
+    }
+
+    #[test]
+    fn test_odt_sortable_orders_chronologically() {
+        // Random, but the same on every run
+        let mut rng = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+        let mut values: Vec<_> = (0..10_000).map(|_| date_time(&mut rng)).collect();
+        let mut encoded: Vec<_> = values.iter().map(|v| odt_sortable(*v).unwrap()).collect();
+
+        values.sort();
+        encoded.sort();
+
+        let decoded: Vec<_> = encoded
+            .iter()
+            .map(|text| super::super::fourth::odt_iteration(text).unwrap())
+            .collect();
+        assert_eq!(values, decoded);
+        assert_eq!(check_order_safe(encoded.iter().map(String::as_str)), Ok(()));
+    }
+
+    #[test]
+    fn test_julian_day() {
+        // Expected values come from SQLite 3.51 `julianday()`.
+        assert_eq!(
+            pdt_julian_day(datetime!(2016-03-07 22:36:55.135)),
+            2457455.442304803
+        );
+        assert_eq!(
+            odt_julian_day(datetime!(2016-03-07 22:36:55.135+3:30)),
+            2457455.29647147
+        );
+        assert_eq!(
+            odt_julian_day(datetime!(2013-09-17 23:59-1)),
+            2456553.540972222
+        );
+        assert_eq!(
+            odt_julian_day(datetime!(2015-11-19 01:01:39+1)),
+            2457345.501145833
+        );
+        assert_eq!(pdt_julian_day(datetime!(2000-01-01 12:00)), 2451545.0);
+        assert_eq!(pdt_julian_day(datetime!(0000-01-01 00:00)), 1721059.5);
+        assert_eq!(
+            pdt_julian_day(datetime!(9999-12-31 23:59:59.999)),
+            5373484.499999989
+        );
+        assert_eq!(
+            pdt_julian_day(datetime!(2014-10-18 00:00:38.0005)),
+            2456948.5004398264
+        );
+        assert_eq!(
+            pdt_julian_day(datetime!(2014-10-18 00:00:38.0015)),
+            2456948.500439838
+        );
+        assert_eq!(
+            pdt_julian_day(datetime!(2014-10-18 00:00:38.6965)),
+            2456948.500447882
+        );
+        assert_eq!(
+            pdt_julian_day(datetime!(2014-10-18 00:00:38.6975)),
+            2456948.5004478935
+        );
+        assert_eq!(
+            pdt_julian_day(datetime!(2014-10-18 00:00:38.9995)),
+            2456948.500451377
+        );
+        assert_eq!(
+            pdt_julian_day(datetime!(1969-12-31 23:59:59.9996)),
+            2440587.4999999884
+        );
+        assert_eq!(
+            pdt_julian_day(datetime!(1970-01-01 00:00:00.999999999)),
+            2440587.5000115624
+        );
+    }
+
+    #[test]
+    fn test_unix_epoch() {
+        // Expected values come from SQLite 3.51 `unixepoch()`.
+        assert_eq!(
+            pdt_unix_epoch(datetime!(2016-03-07 22:36:55.135)),
+            1457390215
+        );
+        assert_eq!(
+            odt_unix_epoch(datetime!(2016-03-07 22:36:55.135+3:30)),
+            1457377615
+        );
+        assert_eq!(odt_unix_epoch(datetime!(2013-09-17 23:59-1)), 1379465940);
+        assert_eq!(pdt_unix_epoch(datetime!(2000-01-01 12:00)), 946728000);
+        assert_eq!(pdt_unix_epoch(datetime!(0000-01-01 00:00)), -62167219200);
+        assert_eq!(
+            pdt_unix_epoch(datetime!(9999-12-31 23:59:59.999)),
+            253402300799
+        );
+        assert_eq!(
+            pdt_unix_epoch(datetime!(2014-10-18 00:00:38.9995)),
+            1413590438
+        );
+        assert_eq!(pdt_unix_epoch(datetime!(1969-12-31 23:59:59.9996)), -1);
+        assert_eq!(pdt_unix_epoch(datetime!(1970-01-01 00:00:00.999999999)), 0);
+    }
+
+    #[test]
+    fn test_check_order_safe() {
+        assert_eq!(
+            check_order_safe(["2013-09-17 23:59-01:00", "2013-09-18 00:01-01:00"]),
+            Ok(())
+        );
+        assert_eq!(
+            check_order_safe(["2013-09-17 23:59-01:00", "not a date"]),
+            Err(OrderHazard::Undecodable { index: 1 })
+        );
+        assert_eq!(
+            check_order_safe(["2013-09-17 23:59+00:00", "2013-09-17T23:59+00:00"]),
+            Err(OrderHazard::MixedLayout { index: 1 })
+        );
+        assert_eq!(
+            check_order_safe(["2013-09-17 23:59:00+00:00", "2013-09-17 23:59+00:00"]),
+            Err(OrderHazard::MixedLayout { index: 1 })
+        );
+        assert_eq!(
+            check_order_safe(["2013-09-17 23:59+00:00", "2013-09-17 23:59+01:00"]),
+            Err(OrderHazard::MixedOffset { index: 1 })
+        );
+        assert_eq!(
+            check_order_safe(["2013-09-17 23:59+01:00", "2013-09-17 23:59-01:00"]),
+            Err(OrderHazard::MixedOffset { index: 1 })
+        );
+        assert_eq!(
+            check_order_safe(["2013-09-17 23:59+00:00", "2013-09-17 23:59:00Z"]),
+            Err(OrderHazard::MixedLayout { index: 1 })
+        );
+        assert_eq!(
+            check_order_safe(["2016-03-07 10:00", "-0001-03-01 10:00"]),
+            Err(OrderHazard::SignedYear { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_check_order_safe_without_offsets() {
+        assert_eq!(
+            check_order_safe([
+                "2013-09-17 23:59:00",
+                "2013-09-18 00:01:00",
+                "2013-09-18 00:02:00"
+            ]),
+            Ok(())
+        );
+        assert_eq!(
+            check_order_safe(["2013-09-17 23:59:00", "2013-09-17T23:59:00"]),
+            Err(OrderHazard::MixedLayout { index: 1 })
+        );
+        assert_eq!(
+            check_order_safe(["2013-09-17 23:59:00", "2013-09-17 23:59:00+00:00"]),
+            Err(OrderHazard::MixedOffset { index: 1 })
+        );
+        assert_eq!(
+            check_order_safe(["2013-09-17 23:59:00Z", "2013-09-17 23:59:00+00:00"]),
+            Err(OrderHazard::MixedLayout { index: 1 })
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use super::walk::{describe, Stop, Walker};
+use std::fmt;
+use time::error::TryFromParsed;
+use time::format_description::FormatItem;
+use time::parsing::Parsed;
+
+/// A format a decoder tries, with the name it is reported under.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Candidate<'a> {
+    pub(crate) name: &'static str,
+    pub(crate) items: &'a [FormatItem<'a>],
+}
+
+/// The reason a value could not be decoded.
+///
+/// Records the input and, for every format the decoder tried, how far into the input that format
+/// matched and what it expected next.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    input: String,
+    attempts: Vec<Attempt>,
+}
+
+/// How far a single candidate format matched before it was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    format: &'static str,
+    position: usize,
+    /// Bytes matched before giving up. An out of range value counts as one more than the whole
+    /// input, so it outranks a format that stopped at the end of the input.
+    progress: usize,
+    problem: Problem,
+}
+
+/// Why a candidate format rejected the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The input did not contain what the format expected at this point.
+    Expected {
+        expected: String,
+        after: Option<String>,
+    },
+    /// The format matched, but the input continued past its end.
+    TrailingCharacters { after: Option<String> },
+    /// Every field matched, but one is out of range, like February 30.
+    OutOfRange { component: &'static str },
+    /// A fallback that cannot be walked item by item did not accept the input.
+    Rejected,
+}
+
+impl Error {
+    /// Tries each of `candidates` against `input` and records where each one stopped matching.
+    pub(crate) fn diagnose<T>(input: &str, candidates: &[Candidate<'_>]) -> Self
+    where
+        T: TryFrom<Parsed, Error = TryFromParsed>,
+    {
+        Self {
+            input: input.to_string(),
+            attempts: candidates
+                .iter()
+                .map(|candidate| Attempt::new::<T>(input, candidate))
+                .collect(),
+        }
+    }
+
+    /// An error explained by a single attempt.
+    pub(crate) fn from_attempt(input: &str, attempt: Attempt) -> Self {
+        Self {
+            input: input.to_string(),
+            attempts: vec![attempt],
+        }
+    }
+
+    /// Adds the attempts of another diagnosis of the same input.
+    pub(crate) fn and(mut self, other: Self) -> Self {
+        self.attempts.extend(other.attempts);
+        self
+    }
+
+    /// Records that each of the named fallbacks rejected the input as well.
+    pub(crate) fn rejected_by(mut self, names: impl IntoIterator<Item = &'static str>) -> Self {
+        self.attempts
+            .extend(names.into_iter().map(|format| Attempt {
+                format,
+                position: 0,
+                progress: 0,
+                problem: Problem::Rejected,
+            }));
+        self
+    }
+
+    /// The text that could not be decoded.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Every candidate format that was tried, in the order the decoder tried them.
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    /// The attempt that matched the most input, which is usually the most useful explanation. A
+    /// value that matched a format but is out of range beats any format that stopped matching.
+    /// Ties go to the format tried first.
+    pub fn furthest(&self) -> Option<&Attempt> {
+        self.attempts
+            .iter()
+            .rev()
+            .max_by_key(|attempt| attempt.progress)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "could not decode `{}`", self.input)?;
+        match self.furthest() {
+            Some(attempt) => write!(f, ": {attempt}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Attempt {
+    fn new<T>(input: &str, candidate: &Candidate<'_>) -> Self
+    where
+        T: TryFrom<Parsed, Error = TryFromParsed>,
+    {
+        let mut walker = Walker::new(input.as_bytes());
+        let matched = walker.items(candidate.items);
+        let expected = |stop: Stop| {
+            let problem = Problem::Expected {
+                expected: describe(stop.expected),
+                after: stop.after.map(describe),
+            };
+            (stop.position, problem)
+        };
+
+        let (position, problem) = match walker.stopped {
+            Some(stop) if !matched => expected(stop),
+            // Something optional could have continued where the input did
+            Some(stop) if !walker.is_done() && stop.position >= walker.position => expected(stop),
+            _ if !walker.is_done() => {
+                let problem = Problem::TrailingCharacters {
+                    after: walker.last.map(describe),
+                };
+                (walker.position, problem)
+            }
+            _ => match T::try_from(walker.parsed) {
+                Err(TryFromParsed::ComponentRange(range)) => {
+                    let component = range.name();
+                    let position = walker.start_of(component).unwrap_or(walker.position);
+                    (position, Problem::OutOfRange { component })
+                }
+                _ => {
+                    let problem = Problem::Expected {
+                        expected: "more fields".to_string(),
+                        after: walker.last.map(describe),
+                    };
+                    (walker.position, problem)
+                }
+            },
+        };
+
+        Self::at(input, candidate.name, position, problem)
+    }
+
+    /// An attempt that stopped at `position`, for decoders that report the problem themselves.
+    pub(crate) fn at(input: &str, format: &'static str, position: usize, problem: Problem) -> Self {
+        let progress = match problem {
+            Problem::OutOfRange { .. } => input.len() + 1,
+            _ => position,
+        };
+
+        Self {
+            format,
+            position,
+            progress,
+            problem,
+        }
+    }
+
+    /// The name of the format that was tried.
+    pub fn format(&self) -> &'static str {
+        self.format
+    }
+
+    /// The byte offset into the input at which the format stopped matching.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Why the format stopped matching.
+    pub fn problem(&self) -> &Problem {
+        &self.problem
+    }
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.problem {
+            Problem::Expected { expected, after } => {
+                write!(f, "expected {expected}")?;
+                if let Some(after) = after {
+                    write!(f, " after {after}")?;
+                }
+            }
+            Problem::TrailingCharacters { after } => {
+                write!(f, "expected end of input")?;
+                if let Some(after) = after {
+                    write!(f, " after {after}")?;
+                }
+            }
+            Problem::OutOfRange { component } => write!(f, "{component} is out of range")?,
+            Problem::Rejected => write!(f, "no match")?,
+        }
+        write!(f, " at byte {} ({})", self.position, self.format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{First, Fourth, Problem, SqliteTemporalParser, Third};
+
+    #[test]
+    fn test_expected_literal() {
+        let error = Fourth
+            .parse_offset_date_time("2013-09-17 23:59x00")
+            .unwrap_err();
+        assert_eq!(error.input(), "2013-09-17 23:59x00");
+
+        let furthest = error.furthest().unwrap();
+        assert_eq!(furthest.position(), 16);
+        assert!(furthest
+            .to_string()
+            .starts_with("expected `:` after minute at byte 16"));
+
+        let rfc3339 = error
+            .attempts()
+            .iter()
+            .find(|attempt| attempt.format() == "RFC 3339")
+            .unwrap();
+        assert_eq!(rfc3339.position(), 10);
+        assert_eq!(
+            rfc3339.problem(),
+            &Problem::Expected {
+                expected: "`T`".to_string(),
+                after: Some("day".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_every_candidate_is_reported() {
+        let error = First.parse_primitive_date_time("2013-09-17").unwrap_err();
+        assert_eq!(error.attempts().len(), 12);
+        assert!(error
+            .attempts()
+            .iter()
+            .all(|attempt| attempt.position() == 10));
+        assert_eq!(
+            error.to_string(),
+            "could not decode `2013-09-17`: expected ` ` after day at byte 10 \
+             ([year]-[month]-[day] [hour]:[minute]:[second])"
+        );
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let error = First
+            .parse_offset_date_time("2016-02-30 10:00+01:00")
+            .unwrap_err();
+        let furthest = error.furthest().unwrap();
+        assert_eq!(furthest.position(), 8);
+        assert_eq!(
+            furthest.problem(),
+            &Problem::OutOfRange { component: "day" }
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_beats_end_of_input() {
+        // The formats with seconds stop at the end of the input, as far as the range error
+        let error = Fourth
+            .parse_primitive_date_time("2016-02-30 12:00")
+            .unwrap_err();
+        assert_eq!(
+            error.furthest().unwrap().problem(),
+            &Problem::OutOfRange { component: "day" }
+        );
+
+        let error = Third.parse_time("23:59:60").unwrap_err();
+        assert_eq!(
+            error.furthest().unwrap().problem(),
+            &Problem::OutOfRange {
+                component: "second"
+            }
+        );
+    }
+
+    #[test]
+    fn test_trailing_characters() {
+        let error = Third.parse_date("2016-03-07 10:00").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "could not decode `2016-03-07 10:00`: expected end of input after day at byte 10 (DATE)"
+        );
+    }
+
+    #[test]
+    fn test_first_alternative() {
+        let error = Fourth
+            .parse_offset_date_time("2016-03-07T22:36:55.135")
+            .unwrap_err();
+        let rfc3339 = error
+            .attempts()
+            .iter()
+            .find(|attempt| attempt.format() == "RFC 3339")
+            .unwrap();
+        assert_eq!(
+            rfc3339.to_string(),
+            "expected one of `Z`, offset hour after subsecond at byte 23 (RFC 3339)"
+        );
+    }
+}
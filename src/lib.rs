@@ -1,7 +1,251 @@
+// The separator of the canonical format has no fallback, so one of its features must choose it
+#[cfg(not(any(
+    feature = "default-format-sqlite-space",
+    feature = "default-format-iso8601-t"
+)))]
+compile_error!("enable `default-format-sqlite-space` or `default-format-iso8601-t`");
+
+use error::Candidate;
+use matched::MatchedFormat;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+pub use error::{Attempt, Error, Problem};
+
+pub mod any;
+pub mod chain;
+pub mod config;
+pub mod encode;
+mod error;
+pub mod matched;
+pub mod profile;
+mod walk;
+
+/// Decodes the SQLite date and time text formats into `time` types.
+///
+/// Each iteration module implements this through a zero-sized strategy type, so tests and
+/// benchmarks can be written once and run against all of them.
+pub trait SqliteTemporalParser {
+    fn parse_offset_date_time(&self, input: &str) -> Result<OffsetDateTime, Error>;
+
+    fn parse_primitive_date_time(&self, input: &str) -> Result<PrimitiveDateTime, Error>;
+
+    fn parse_time(&self, input: &str) -> Result<Time, Error>;
+
+    fn parse_date(&self, input: &str) -> Result<Date, Error>;
+
+    /// This decoder limited to the text `profile` also accepts. Values are still decoded by this
+    /// decoder.
+    fn with_profile(self, profile: profile::Profile) -> profile::Profiled<Self>
+    where
+        Self: Sized,
+    {
+        profile::Profiled::new(self, profile)
+    }
+}
+
+/// A [`SqliteTemporalParser`] that also reports which of SQLite's formats the text was in.
+pub trait FormatReportingParser: SqliteTemporalParser {
+    fn parse_offset_date_time_with_format(
+        &self,
+        input: &str,
+    ) -> Result<(OffsetDateTime, MatchedFormat), Error>;
+
+    fn parse_primitive_date_time_with_format(
+        &self,
+        input: &str,
+    ) -> Result<(PrimitiveDateTime, MatchedFormat), Error>;
+
+    fn parse_time_with_format(&self, input: &str) -> Result<(Time, MatchedFormat), Error>;
+}
+
+/// The [`first`] iteration: a loop over `format_description!` macros.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct First;
+
+/// The [`second`] iteration: macros concatenated with `FormatItem::Optional` items.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Second;
+
+/// The [`third`] iteration: a fast path, then space and `T` separated descriptions.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Third;
+
+/// The [`fourth`] iteration: a fast path, then one description with optional separators.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Fourth;
+
+impl SqliteTemporalParser for First {
+    fn parse_offset_date_time(&self, input: &str) -> Result<OffsetDateTime, Error> {
+        first::odt_iteration(input)
+            .ok_or_else(|| Error::diagnose::<OffsetDateTime>(input, first::ODT_CANDIDATES))
+    }
+
+    fn parse_primitive_date_time(&self, input: &str) -> Result<PrimitiveDateTime, Error> {
+        first::pdt_iteration(input)
+            .ok_or_else(|| Error::diagnose::<PrimitiveDateTime>(input, first::PDT_CANDIDATES))
+    }
+
+    fn parse_time(&self, input: &str) -> Result<Time, Error> {
+        first::time_iteration(input)
+            .ok_or_else(|| Error::diagnose::<Time>(input, first::TIME_CANDIDATES))
+    }
+
+    fn parse_date(&self, input: &str) -> Result<Date, Error> {
+        first::date_iteration(input)
+            .ok_or_else(|| Error::diagnose::<Date>(input, first::DATE_CANDIDATES))
+    }
+}
+
+macro_rules! impl_parser {
+    ($strategy:ident, $module:ident) => {
+        impl SqliteTemporalParser for $strategy {
+            fn parse_offset_date_time(&self, input: &str) -> Result<OffsetDateTime, Error> {
+                $module::odt_iteration(input)
+                    .map_err(|_| Error::diagnose::<OffsetDateTime>(input, $module::ODT_CANDIDATES))
+            }
+
+            fn parse_primitive_date_time(&self, input: &str) -> Result<PrimitiveDateTime, Error> {
+                $module::pdt_iteration(input).map_err(|_| {
+                    Error::diagnose::<PrimitiveDateTime>(input, $module::PDT_CANDIDATES)
+                })
+            }
+
+            fn parse_time(&self, input: &str) -> Result<Time, Error> {
+                $module::time_iteration(input)
+                    .map_err(|_| Error::diagnose::<Time>(input, $module::TIME_CANDIDATES))
+            }
+
+            fn parse_date(&self, input: &str) -> Result<Date, Error> {
+                $module::date_iteration(input)
+                    .map_err(|_| Error::diagnose::<Date>(input, $module::DATE_CANDIDATES))
+            }
+        }
+    };
+}
+
+impl_parser!(Second, second);
+impl_parser!(Third, third);
+impl_parser!(Fourth, fourth);
+
+macro_rules! impl_format_reporting_parser {
+    ($strategy:ident, $module:ident) => {
+        impl FormatReportingParser for $strategy {
+            fn parse_offset_date_time_with_format(
+                &self,
+                input: &str,
+            ) -> Result<(OffsetDateTime, MatchedFormat), Error> {
+                $module::odt_iteration_with_format(input)
+            }
+
+            fn parse_primitive_date_time_with_format(
+                &self,
+                input: &str,
+            ) -> Result<(PrimitiveDateTime, MatchedFormat), Error> {
+                $module::pdt_iteration_with_format(input)
+            }
+
+            fn parse_time_with_format(&self, input: &str) -> Result<(Time, MatchedFormat), Error> {
+                $module::time_iteration_with_format(input)
+            }
+        }
+    };
+}
+
+impl_format_reporting_parser!(First, first);
+impl_format_reporting_parser!(Second, second);
+impl_format_reporting_parser!(Third, third);
+impl_format_reporting_parser!(Fourth, fourth);
+
+/// Adds the `*_iteration_with_format` functions to an iteration module. They walk the module's
+/// candidate formats in the order its iterations try them, so both decode the same text the same
+/// way, and read the format off the walk that produced the value.
+macro_rules! iterations_with_format {
+    () => {
+        /// Like [`odt_iteration`], but also reports which format the text was in. The format is
+        /// read off the same pass that produces the value.
+        pub fn odt_iteration_with_format(
+            offset_date_time_string: &str,
+        ) -> Result<(time::OffsetDateTime, $crate::matched::MatchedFormat), $crate::Error> {
+            $crate::matched::decode_odt(offset_date_time_string, ODT_CANDIDATES).ok_or_else(|| {
+                $crate::Error::diagnose::<time::OffsetDateTime>(
+                    offset_date_time_string,
+                    ODT_CANDIDATES,
+                )
+            })
+        }
+
+        /// Like [`pdt_iteration`], but also reports which format the text was in.
+        pub fn pdt_iteration_with_format(
+            primitive_date_time_string: &str,
+        ) -> Result<(time::PrimitiveDateTime, $crate::matched::MatchedFormat), $crate::Error> {
+            $crate::matched::decode(primitive_date_time_string, PDT_CANDIDATES).ok_or_else(|| {
+                $crate::Error::diagnose::<time::PrimitiveDateTime>(
+                    primitive_date_time_string,
+                    PDT_CANDIDATES,
+                )
+            })
+        }
+
+        /// Like [`time_iteration`], but also reports which format the text was in.
+        pub fn time_iteration_with_format(
+            time_string: &str,
+        ) -> Result<(time::Time, $crate::matched::MatchedFormat), $crate::Error> {
+            $crate::matched::decode(time_string, TIME_CANDIDATES)
+                .ok_or_else(|| $crate::Error::diagnose::<time::Time>(time_string, TIME_CANDIDATES))
+        }
+    };
+}
+
+/// Names a `format_description!` after its own text, for error reporting.
+macro_rules! candidate {
+    ($description:tt) => {
+        Candidate {
+            name: $description,
+            items: fd!($description),
+        }
+    };
+}
+
 pub mod first {
+    use super::formats::RFC3339;
+    use super::Candidate;
     use time::format_description::well_known::Rfc3339;
     use time::macros::format_description as fd;
-    use time::{OffsetDateTime, PrimitiveDateTime, Time};
+    use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+    pub(crate) const ODT_CANDIDATES: &[Candidate] = &[
+        Candidate {
+            name: "RFC 3339",
+            items: RFC3339,
+        },
+        candidate!("[year]-[month]-[day] [hour]:[minute]:[second][offset_hour sign:mandatory]:[offset_minute]"),
+        candidate!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond][offset_hour sign:mandatory]:[offset_minute]"),
+        candidate!("[year]-[month]-[day] [hour]:[minute][offset_hour sign:mandatory]:[offset_minute]"),
+        candidate!("[year]-[month]-[day]T[hour]:[minute][offset_hour sign:mandatory]:[offset_minute]"),
+    ];
+
+    pub(crate) const PDT_CANDIDATES: &[Candidate] = &[
+        candidate!("[year]-[month]-[day] [hour]:[minute]:[second]"),
+        candidate!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond]"),
+        candidate!("[year]-[month]-[day] [hour]:[minute]"),
+        candidate!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
+        candidate!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]"),
+        candidate!("[year]-[month]-[day]T[hour]:[minute]"),
+        candidate!("[year]-[month]-[day] [hour]:[minute]:[second]Z"),
+        candidate!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond]Z"),
+        candidate!("[year]-[month]-[day] [hour]:[minute]Z"),
+        candidate!("[year]-[month]-[day]T[hour]:[minute]:[second]Z"),
+        candidate!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]Z"),
+        candidate!("[year]-[month]-[day]T[hour]:[minute]Z"),
+    ];
+
+    pub(crate) const TIME_CANDIDATES: &[Candidate] = &[
+        candidate!("[hour]:[minute]:[second]"),
+        candidate!("[hour]:[minute]:[second].[subsecond]"),
+        candidate!("[hour]:[minute]"),
+    ];
+
+    pub(crate) const DATE_CANDIDATES: &[Candidate] = &[candidate!("[year]-[month]-[day]")];
 
     pub fn odt_iteration(offset_date_time_string: &str) -> Option<OffsetDateTime> {
         if let Ok(dt) = OffsetDateTime::parse(offset_date_time_string, &Rfc3339) {
@@ -66,12 +310,76 @@ pub mod first {
 
         None
     }
+
+    pub fn date_iteration(date_string: &str) -> Option<Date> {
+        Date::parse(date_string, fd!("[year]-[month]-[day]")).ok()
+    }
+
+    iterations_with_format!();
 }
 
 pub mod second {
+    use super::Candidate;
     use time::format_description::FormatItem::*;
     use time::macros::format_description as fd;
-    use time::{error::Parse, OffsetDateTime, PrimitiveDateTime, Time};
+    use time::{error::Parse, Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+    pub(crate) const ODT_CANDIDATES: &[Candidate] = &[
+        Candidate {
+            name: "space separated",
+            items: &[
+                Compound(fd!("[year]-[month]-[day] [hour]:[minute]")),
+                Optional(&Compound(fd!(":[second]"))),
+                Optional(&Compound(fd!(".[subsecond]"))),
+                Optional(&Compound(fd!(
+                    "[offset_hour sign:mandatory]:[offset_minute]"
+                ))),
+            ],
+        },
+        Candidate {
+            name: "T separated",
+            items: &[
+                Compound(fd!("[year]-[month]-[day]T[hour]:[minute]")),
+                Optional(&Compound(fd!(":[second]"))),
+                Optional(&Compound(fd!(".[subsecond]"))),
+                Optional(&Compound(fd!(
+                    "[offset_hour sign:mandatory]:[offset_minute]"
+                ))),
+            ],
+        },
+    ];
+
+    pub(crate) const PDT_CANDIDATES: &[Candidate] = &[
+        Candidate {
+            name: "space separated",
+            items: &[
+                Compound(fd!("[year]-[month]-[day] [hour]:[minute]")),
+                Optional(&Compound(fd!(":[second]"))),
+                Optional(&Compound(fd!(".[subsecond]"))),
+                Optional(&Literal(b"Z")),
+            ],
+        },
+        Candidate {
+            name: "T separated",
+            items: &[
+                Compound(fd!("[year]-[month]-[day]T[hour]:[minute]")),
+                Optional(&Compound(fd!(":[second]"))),
+                Optional(&Compound(fd!(".[subsecond]"))),
+                Optional(&Literal(b"Z")),
+            ],
+        },
+    ];
+
+    pub(crate) const TIME_CANDIDATES: &[Candidate] = &[Candidate {
+        name: "[hour]:[minute] with optional seconds",
+        items: &[
+            Compound(fd!("[hour]:[minute]")),
+            Optional(&Compound(fd!(":[second]"))),
+            Optional(&Compound(fd!(".[subsecond]"))),
+        ],
+    }];
+
+    pub(crate) const DATE_CANDIDATES: &[Candidate] = &[candidate!("[year]-[month]-[day]")];
 
     pub fn odt_iteration(offset_date_time_string: &str) -> Result<OffsetDateTime, Parse> {
         let ymd = fd!("[year]-[month]-[day]");
@@ -127,16 +435,75 @@ pub mod second {
         let descriptions = [Compound(&full_description[..])];
         Time::parse(time_string, &First(&descriptions))
     }
+
+    pub fn date_iteration(date_string: &str) -> Result<Date, Parse> {
+        Date::parse(date_string, fd!("[year]-[month]-[day]"))
+    }
+
+    iterations_with_format!();
 }
 
 pub mod third {
     use super::formats::*;
+    use super::Candidate;
     use time::format_description::well_known::Rfc3339;
     use time::format_description::FormatItem::*;
-    use time::macros::format_description as fd;
-    use time::{error::Parse, OffsetDateTime, PrimitiveDateTime, Time};
+    use time::{error::Parse, Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+    pub(crate) const ODT_CANDIDATES: &[Candidate] = &[
+        Candidate {
+            name: "DEFAULT_OFFSET_DATE_TIME",
+            items: DEFAULT_OFFSET_DATE_TIME,
+        },
+        Candidate {
+            name: "RFC 3339",
+            items: RFC3339,
+        },
+        Candidate {
+            name: "OFFSET_DATE_TIME_SPACE_SEPARATED",
+            items: OFFSET_DATE_TIME_SPACE_SEPARATED,
+        },
+        Candidate {
+            name: "OFFSET_DATE_TIME_T_SEPARATED",
+            items: OFFSET_DATE_TIME_T_SEPARATED,
+        },
+    ];
+
+    pub(crate) const PDT_CANDIDATES: &[Candidate] = &[
+        Candidate {
+            name: "DEFAULT_PRIMITIVE_DATE_TIME",
+            items: DEFAULT_PRIMITIVE_DATE_TIME,
+        },
+        Candidate {
+            name: "PRIMITIVE_DATE_TIME_SPACE_SEPARATED",
+            items: PRIMITIVE_DATE_TIME_SPACE_SEPARATED,
+        },
+        Candidate {
+            name: "PRIMITIVE_DATE_TIME_T_SEPARATED",
+            items: PRIMITIVE_DATE_TIME_T_SEPARATED,
+        },
+    ];
+
+    pub(crate) const TIME_CANDIDATES: &[Candidate] = &[
+        Candidate {
+            name: "DEFAULT_TIME",
+            items: DEFAULT_TIME,
+        },
+        Candidate {
+            name: "TIME_DESCRIPTION",
+            items: TIME_DESCRIPTION,
+        },
+    ];
+
+    pub(crate) const DATE_CANDIDATES: &[Candidate] = &[Candidate {
+        name: "DATE",
+        items: DATE,
+    }];
 
     pub fn odt_iteration(offset_date_time_string: &str) -> Result<OffsetDateTime, Parse> {
+        if let Ok(dt) = OffsetDateTime::parse(offset_date_time_string, DEFAULT_OFFSET_DATE_TIME) {
+            return Ok(dt);
+        }
         if let Ok(dt) = OffsetDateTime::parse(offset_date_time_string, &Rfc3339) {
             return Ok(dt);
         }
@@ -151,8 +518,9 @@ pub mod third {
     }
 
     pub fn pdt_iteration(primitive_date_time_string: &str) -> Result<PrimitiveDateTime, Parse> {
-        let default_format = fd!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond]");
-        if let Ok(dt) = PrimitiveDateTime::parse(primitive_date_time_string, &default_format) {
+        if let Ok(dt) =
+            PrimitiveDateTime::parse(primitive_date_time_string, DEFAULT_PRIMITIVE_DATE_TIME)
+        {
             return Ok(dt);
         }
 
@@ -166,22 +534,66 @@ pub mod third {
     }
 
     pub fn time_iteration(time_string: &str) -> Result<Time, Parse> {
-        let default_format = fd!("[hour]:[minute]:[second].[subsecond]");
-        if let Ok(dt) = Time::parse(time_string, &default_format) {
+        if let Ok(dt) = Time::parse(time_string, DEFAULT_TIME) {
             return Ok(dt);
         }
 
         Time::parse(time_string, TIME_DESCRIPTION)
     }
+
+    pub fn date_iteration(date_string: &str) -> Result<Date, Parse> {
+        Date::parse(date_string, DATE)
+    }
+
+    iterations_with_format!();
 }
 
 pub mod fourth {
     use super::formats::*;
+    use super::Candidate;
     use time::format_description::well_known::Rfc3339;
-    use time::macros::format_description as fd;
-    use time::{error::Parse, OffsetDateTime, PrimitiveDateTime};
+    use time::{error::Parse, Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+    pub(crate) const ODT_CANDIDATES: &[Candidate] = &[
+        Candidate {
+            name: "DEFAULT_OFFSET_DATE_TIME",
+            items: DEFAULT_OFFSET_DATE_TIME,
+        },
+        Candidate {
+            name: "RFC 3339",
+            items: RFC3339,
+        },
+        Candidate {
+            name: "OFFSET_DATE_TIME",
+            items: OFFSET_DATE_TIME,
+        },
+    ];
+
+    pub(crate) const PDT_CANDIDATES: &[Candidate] = &[
+        Candidate {
+            name: "DEFAULT_PRIMITIVE_DATE_TIME",
+            items: DEFAULT_PRIMITIVE_DATE_TIME,
+        },
+        Candidate {
+            name: "PRIMITIVE_DATE_TIME",
+            items: PRIMITIVE_DATE_TIME,
+        },
+    ];
+
+    pub(crate) const TIME_CANDIDATES: &[Candidate] = &[Candidate {
+        name: "TIME_DESCRIPTION",
+        items: TIME_DESCRIPTION,
+    }];
+
+    pub(crate) const DATE_CANDIDATES: &[Candidate] = &[Candidate {
+        name: "DATE",
+        items: DATE,
+    }];
 
     pub fn odt_iteration(offset_date_time_string: &str) -> Result<OffsetDateTime, Parse> {
+        if let Ok(dt) = OffsetDateTime::parse(offset_date_time_string, DEFAULT_OFFSET_DATE_TIME) {
+            return Ok(dt);
+        }
         if let Ok(dt) = OffsetDateTime::parse(offset_date_time_string, &Rfc3339) {
             return Ok(dt);
         }
@@ -190,19 +602,38 @@ pub mod fourth {
     }
 
     pub fn pdt_iteration(primitive_date_time_string: &str) -> Result<PrimitiveDateTime, Parse> {
-        let default_format = fd!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond]");
-        if let Ok(dt) = PrimitiveDateTime::parse(primitive_date_time_string, &default_format) {
+        if let Ok(dt) =
+            PrimitiveDateTime::parse(primitive_date_time_string, DEFAULT_PRIMITIVE_DATE_TIME)
+        {
             return Ok(dt);
         }
 
         PrimitiveDateTime::parse(primitive_date_time_string, PRIMITIVE_DATE_TIME)
     }
+
+    pub fn time_iteration(time_string: &str) -> Result<Time, Parse> {
+        // A time has a single root already, so there is no fast path to try first
+        Time::parse(time_string, TIME_DESCRIPTION)
+    }
+
+    pub fn date_iteration(date_string: &str) -> Result<Date, Parse> {
+        Date::parse(date_string, DATE)
+    }
+
+    iterations_with_format!();
 }
 
-mod formats {
+/// The `FormatItem`s the iterations, encoders and profiles are built from.
+///
+/// The components are what the equivalent `format_description!` would produce, for example
+/// [`YEAR`](formats::YEAR) is `[year]` and [`OFFSET_HOUR`](formats::OFFSET_HOUR) is
+/// `[offset_hour sign:mandatory]`, so descriptions built from them can be compared with the macro
+/// like for like. The composed descriptions are the ones the decoders and encoders use.
+pub mod formats {
     use time::format_description::{modifier, Component::*, FormatItem, FormatItem::*};
 
-    const YEAR: FormatItem = Component(Year({
+    /// `[year]`
+    pub const YEAR: FormatItem = Component(Year({
         let mut value = modifier::Year::default();
         value.padding = modifier::Padding::Zero;
         value.repr = modifier::YearRepr::Full;
@@ -211,7 +642,8 @@ mod formats {
         value
     }));
 
-    const MONTH: FormatItem = Component(Month({
+    /// `[month]`
+    pub const MONTH: FormatItem = Component(Month({
         let mut value = modifier::Month::default();
         value.padding = modifier::Padding::Zero;
         value.repr = modifier::MonthRepr::Numerical;
@@ -219,50 +651,180 @@ mod formats {
         value
     }));
 
-    const DAY: FormatItem = Component(Day({
+    /// `[day]`
+    pub const DAY: FormatItem = Component(Day({
         let mut value = modifier::Day::default();
         value.padding = modifier::Padding::Zero;
         value
     }));
 
-    const HOUR: FormatItem = Component(Hour({
+    /// `[hour]`
+    pub const HOUR: FormatItem = Component(Hour({
         let mut value = modifier::Hour::default();
         value.padding = modifier::Padding::Zero;
         value.is_12_hour_clock = false;
         value
     }));
 
-    const MINUTE: FormatItem = Component(Minute({
+    /// `[minute]`
+    pub const MINUTE: FormatItem = Component(Minute({
         let mut value = modifier::Minute::default();
         value.padding = modifier::Padding::Zero;
         value
     }));
 
-    const SECOND: FormatItem = Component(Second({
+    /// `[second]`
+    pub const SECOND: FormatItem = Component(Second({
         let mut value = modifier::Second::default();
         value.padding = modifier::Padding::Zero;
         value
     }));
 
-    const SUBSECOND: FormatItem = Component(Subsecond({
+    /// `[subsecond]`, one or more digits.
+    pub const SUBSECOND: FormatItem = Component(Subsecond({
         let mut value = modifier::Subsecond::default();
         value.digits = modifier::SubsecondDigits::OneOrMore;
         value
     }));
 
-    const OFFSET_HOUR: FormatItem = Component(OffsetHour({
+    const SUBSECOND_NINE: FormatItem = Component(Subsecond({
+        let mut value = modifier::Subsecond::default();
+        value.digits = modifier::SubsecondDigits::Nine;
+        value
+    }));
+
+    #[cfg(not(feature = "default-format-millis"))]
+    const DEFAULT_SUBSECOND: FormatItem = SUBSECOND;
+
+    #[cfg(feature = "default-format-millis")]
+    const DEFAULT_SUBSECOND: FormatItem = Component(Subsecond({
+        let mut value = modifier::Subsecond::default();
+        value.digits = modifier::SubsecondDigits::Three;
+        value
+    }));
+
+    #[cfg(all(
+        feature = "default-format-sqlite-space",
+        not(feature = "default-format-iso8601-t")
+    ))]
+    const DEFAULT_SEPARATOR: FormatItem = Literal(b" ");
+
+    #[cfg(feature = "default-format-iso8601-t")]
+    const DEFAULT_SEPARATOR: FormatItem = Literal(b"T");
+
+    /// `[offset_hour sign:mandatory]`
+    pub const OFFSET_HOUR: FormatItem = Component(OffsetHour({
         let mut value = modifier::OffsetHour::default();
         value.sign_is_mandatory = true;
         value.padding = modifier::Padding::Zero;
         value
     }));
 
-    const OFFSET_MINUTE: FormatItem = Component(OffsetMinute({
+    /// `[offset_minute]`
+    pub const OFFSET_MINUTE: FormatItem = Component(OffsetMinute({
         let mut value = modifier::OffsetMinute::default();
         value.padding = modifier::Padding::Zero;
         value
     }));
 
+    /// Diagnostic stand-in for `well_known::Rfc3339`, which cannot be parsed item by item. It
+    /// ignores the lowercase `t`/`z` and leap seconds that `Rfc3339` also accepts.
+    pub const RFC3339: &[FormatItem<'_>] = {
+        &[
+            YEAR,
+            Literal(b"-"),
+            MONTH,
+            Literal(b"-"),
+            DAY,
+            Literal(b"T"),
+            HOUR,
+            Literal(b":"),
+            MINUTE,
+            Literal(b":"),
+            SECOND,
+            Optional(&Compound(&[Literal(b"."), SUBSECOND])),
+            First(&[
+                Literal(b"Z"),
+                Compound(&[OFFSET_HOUR, Literal(b":"), OFFSET_MINUTE]),
+            ]),
+        ]
+    };
+
+    /// `YYYY-MM-DD`, SQLite's format 1.
+    pub const DATE: &[FormatItem<'_>] = &[YEAR, Literal(b"-"), MONTH, Literal(b"-"), DAY];
+
+    // Pieces that `ParserConfig` assembles descriptions from
+
+    /// `HH:MM`
+    pub const HOUR_MINUTE: FormatItem = Compound(&[HOUR, Literal(b":"), MINUTE]);
+
+    /// `:SS`
+    pub const SECONDS: FormatItem = Compound(&[Literal(b":"), SECOND]);
+
+    /// `.` and one or more subsecond digits.
+    pub const FRACTION: FormatItem = Compound(&[Literal(b"."), SUBSECOND]);
+
+    /// `:SS` with an optional fraction.
+    pub const SECONDS_AND_FRACTION: FormatItem = Compound(&[SECONDS, Optional(&FRACTION)]);
+
+    /// `+HH:MM` or `-HH:MM`
+    pub const NUMERIC_OFFSET: FormatItem = Compound(&[OFFSET_HOUR, Literal(b":"), OFFSET_MINUTE]);
+
+    /// The canonical format chosen by the `default-format-*` features, with an offset.
+    pub const DEFAULT_OFFSET_DATE_TIME: &[FormatItem<'_>] = {
+        &[
+            YEAR,
+            Literal(b"-"),
+            MONTH,
+            Literal(b"-"),
+            DAY,
+            DEFAULT_SEPARATOR,
+            HOUR,
+            Literal(b":"),
+            MINUTE,
+            Literal(b":"),
+            SECOND,
+            Literal(b"."),
+            DEFAULT_SUBSECOND,
+            OFFSET_HOUR,
+            Literal(b":"),
+            OFFSET_MINUTE,
+        ]
+    };
+
+    /// The canonical format chosen by the `default-format-*` features.
+    pub const DEFAULT_PRIMITIVE_DATE_TIME: &[FormatItem<'_>] = {
+        &[
+            YEAR,
+            Literal(b"-"),
+            MONTH,
+            Literal(b"-"),
+            DAY,
+            DEFAULT_SEPARATOR,
+            HOUR,
+            Literal(b":"),
+            MINUTE,
+            Literal(b":"),
+            SECOND,
+            Literal(b"."),
+            DEFAULT_SUBSECOND,
+        ]
+    };
+
+    /// The time of day in the canonical format chosen by the `default-format-*` features.
+    pub const DEFAULT_TIME: &[FormatItem<'_>] = {
+        &[
+            HOUR,
+            Literal(b":"),
+            MINUTE,
+            Literal(b":"),
+            SECOND,
+            Literal(b"."),
+            DEFAULT_SUBSECOND,
+        ]
+    };
+
+    /// Formats 2 to 7 with an optional offset, and either separator or none.
     pub const OFFSET_DATE_TIME: &[FormatItem<'_>] = {
         &[
             YEAR,
@@ -285,6 +847,7 @@ mod formats {
         ]
     };
 
+    /// Formats 2 to 7 with an optional `Z`, and either separator or none.
     pub const PRIMITIVE_DATE_TIME: &[FormatItem<'_>] = {
         &[
             YEAR,
@@ -305,6 +868,7 @@ mod formats {
         ]
     };
 
+    /// Formats 2 to 4 with an optional offset.
     pub const OFFSET_DATE_TIME_SPACE_SEPARATED: &[FormatItem<'_>] = {
         &[
             YEAR,
@@ -326,6 +890,7 @@ mod formats {
         ]
     };
 
+    /// Formats 5 to 7 with an optional offset.
     pub const OFFSET_DATE_TIME_T_SEPARATED: &[FormatItem<'_>] = {
         &[
             YEAR,
@@ -347,6 +912,7 @@ mod formats {
         ]
     };
 
+    /// Formats 2 to 4 with an optional `Z`.
     pub const PRIMITIVE_DATE_TIME_SPACE_SEPARATED: &[FormatItem<'_>] = {
         &[
             YEAR,
@@ -366,6 +932,7 @@ mod formats {
         ]
     };
 
+    /// Formats 5 to 7 with an optional `Z`.
     pub const PRIMITIVE_DATE_TIME_T_SEPARATED: &[FormatItem<'_>] = {
         &[
             YEAR,
@@ -385,6 +952,27 @@ mod formats {
         ]
     };
 
+    /// `YYYY-MM-DD HH:MM:SS.fffffffff+00:00`, written by `encode::odt_sortable`.
+    pub const SORTABLE_UTC: &[FormatItem<'_>] = {
+        &[
+            YEAR,
+            Literal(b"-"),
+            MONTH,
+            Literal(b"-"),
+            DAY,
+            Literal(b" "),
+            HOUR,
+            Literal(b":"),
+            MINUTE,
+            Literal(b":"),
+            SECOND,
+            Literal(b"."),
+            SUBSECOND_NINE,
+            Literal(b"+00:00"),
+        ]
+    };
+
+    /// Formats 8 to 10.
     pub const TIME_DESCRIPTION: &[FormatItem<'_>] = {
         &[
             HOUR,
@@ -397,341 +985,3 @@ mod formats {
         ]
     };
 }
-
-#[cfg(test)]
-mod tests {
-    use crate::{first, fourth, second, third};
-    use time::macros::{datetime, time};
-
-    macro_rules! assert_parsed {
-        ($parse:expr, $object:expr) => {
-            let result = $parse.unwrap();
-            assert_eq!($object, result);
-        };
-    }
-
-    #[test]
-    fn test_odt_first_iteration() {
-        assert_parsed!(
-            first::odt_iteration("2016-03-07T22:36:55.135+03:30"),
-            datetime!(2016-3-7 22:36:55.135+3:30)
-        );
-        assert_parsed!(
-            first::odt_iteration("2015-11-19 01:01:39+01:00"),
-            datetime!(2015-11-19 01:01:39+1)
-        );
-        assert_parsed!(
-            first::odt_iteration("2014-10-18 00:00:38.697+00:00"),
-            datetime!(2014-10-18 00:00:38.697+0)
-        );
-        assert_parsed!(
-            first::odt_iteration("2013-09-17 23:59-01:00"),
-            datetime!(2013-09-17 23:59-1)
-        );
-        assert_parsed!(
-            first::odt_iteration("2017-04-11T14:35+02:00"),
-            datetime!(2017-04-11 14:35+2)
-        );
-    }
-
-    #[test]
-    fn test_odt_second_iteration() {
-        assert_parsed!(
-            second::odt_iteration("2016-03-07T22:36:55.135+03:30"),
-            datetime!(2016-3-7 22:36:55.135+3:30)
-        );
-        assert_parsed!(
-            second::odt_iteration("2015-11-19 01:01:39+01:00"),
-            datetime!(2015-11-19 01:01:39+1)
-        );
-        assert_parsed!(
-            second::odt_iteration("2014-10-18 00:00:38.697+00:00"),
-            datetime!(2014-10-18 00:00:38.697+0)
-        );
-        assert_parsed!(
-            second::odt_iteration("2013-09-17 23:59-01:00"),
-            datetime!(2013-09-17 23:59-1)
-        );
-        assert_parsed!(
-            second::odt_iteration("2017-04-11T14:35+02:00"),
-            datetime!(2017-04-11 14:35+2)
-        );
-    }
-
-    #[test]
-    fn test_odt_third_iteration() {
-        assert_parsed!(
-            third::odt_iteration("2016-03-07T22:36:55.135+03:30"),
-            datetime!(2016-3-7 22:36:55.135+3:30)
-        );
-        assert_parsed!(
-            third::odt_iteration("2015-11-19 01:01:39+01:00"),
-            datetime!(2015-11-19 01:01:39+1)
-        );
-        assert_parsed!(
-            third::odt_iteration("2014-10-18 00:00:38.697+00:00"),
-            datetime!(2014-10-18 00:00:38.697+0)
-        );
-        assert_parsed!(
-            third::odt_iteration("2013-09-17 23:59-01:00"),
-            datetime!(2013-09-17 23:59-1)
-        );
-        assert_parsed!(
-            third::odt_iteration("2017-04-11T14:35+02:00"),
-            datetime!(2017-04-11 14:35+2)
-        );
-    }
-
-    #[test]
-    fn test_odt_fourth_iteration() {
-        assert_parsed!(
-            fourth::odt_iteration("2016-03-07T22:36:55.135+03:30"),
-            datetime!(2016-3-7 22:36:55.135+3:30)
-        );
-        assert_parsed!(
-            fourth::odt_iteration("2015-11-19 01:01:39+01:00"),
-            datetime!(2015-11-19 01:01:39+1)
-        );
-        assert_parsed!(
-            fourth::odt_iteration("2014-10-18 00:00:38.697+00:00"),
-            datetime!(2014-10-18 00:00:38.697+0)
-        );
-        assert_parsed!(
-            fourth::odt_iteration("2013-09-17 23:59-01:00"),
-            datetime!(2013-09-17 23:59-1)
-        );
-        assert_parsed!(
-            fourth::odt_iteration("2017-04-11T14:35+02:00"),
-            datetime!(2017-04-11 14:35+2)
-        );
-    }
-
-    #[test]
-    fn test_pdt_first_iteration() {
-        assert_parsed!(
-            first::pdt_iteration("2014-08-27T00:05"),
-            datetime!(2014-08-27 00:05)
-        );
-        assert_parsed!(
-            first::pdt_iteration("2019-01-02 05:10:20"),
-            datetime!(2019-01-02 05:10:20)
-        );
-        assert_parsed!(
-            first::pdt_iteration("2018-12-01 04:09:19.543"),
-            datetime!(2018-12-01 04:09:19.543)
-        );
-        assert_parsed!(
-            first::pdt_iteration("2017-11-30 03:08"),
-            datetime!(2017-11-30 03:08)
-        );
-        assert_parsed!(
-            first::pdt_iteration("2016-10-29T02:07:17"),
-            datetime!(2016-10-29 02:07:17)
-        );
-        assert_parsed!(
-            first::pdt_iteration("2015-09-28T01:06:16.432"),
-            datetime!(2015-09-28 01:06:16.432)
-        );
-        assert_parsed!(
-            first::pdt_iteration("2012-06-25 22:03:13.321Z"),
-            datetime!(2012-06-25 22:03:13.321)
-        );
-        assert_parsed!(
-            first::pdt_iteration("2009-03-22T19:00:10.21Z"),
-            datetime!(2009-03-22 19:00:10.21)
-        );
-        assert_parsed!(
-            first::pdt_iteration("2013-07-26 23:04:14Z"),
-            datetime!(2013-07-26 23:04:14)
-        );
-        assert_parsed!(
-            first::pdt_iteration("2011-05-24 21:02Z"),
-            datetime!(2011-05-24 21:02)
-        );
-        assert_parsed!(
-            first::pdt_iteration("2010-04-23T20:01:11Z"),
-            datetime!(2010-04-23 20:01:11)
-        );
-        assert_parsed!(
-            first::pdt_iteration("2008-02-21T18:59Z"),
-            datetime!(2008-02-21 18:59)
-        );
-    }
-
-    #[test]
-    fn test_pdt_second_iteration() {
-        assert_parsed!(
-            second::pdt_iteration("2014-08-27T00:05"),
-            datetime!(2014-08-27 00:05)
-        );
-        assert_parsed!(
-            second::pdt_iteration("2019-01-02 05:10:20"),
-            datetime!(2019-01-02 05:10:20)
-        );
-        assert_parsed!(
-            second::pdt_iteration("2018-12-01 04:09:19.543"),
-            datetime!(2018-12-01 04:09:19.543)
-        );
-        assert_parsed!(
-            second::pdt_iteration("2017-11-30 03:08"),
-            datetime!(2017-11-30 03:08)
-        );
-        assert_parsed!(
-            second::pdt_iteration("2016-10-29T02:07:17"),
-            datetime!(2016-10-29 02:07:17)
-        );
-        assert_parsed!(
-            second::pdt_iteration("2015-09-28T01:06:16.432"),
-            datetime!(2015-09-28 01:06:16.432)
-        );
-        assert_parsed!(
-            second::pdt_iteration("2012-06-25 22:03:13.321Z"),
-            datetime!(2012-06-25 22:03:13.321)
-        );
-        assert_parsed!(
-            second::pdt_iteration("2009-03-22T19:00:10.21Z"),
-            datetime!(2009-03-22 19:00:10.21)
-        );
-        assert_parsed!(
-            second::pdt_iteration("2013-07-26 23:04:14Z"),
-            datetime!(2013-07-26 23:04:14)
-        );
-        assert_parsed!(
-            second::pdt_iteration("2011-05-24 21:02Z"),
-            datetime!(2011-05-24 21:02)
-        );
-        assert_parsed!(
-            second::pdt_iteration("2010-04-23T20:01:11Z"),
-            datetime!(2010-04-23 20:01:11)
-        );
-        assert_parsed!(
-            second::pdt_iteration("2008-02-21T18:59Z"),
-            datetime!(2008-02-21 18:59)
-        );
-    }
-
-    #[test]
-    fn test_pdt_third_iteration() {
-        assert_parsed!(
-            third::pdt_iteration("2014-08-27T00:05"),
-            datetime!(2014-08-27 00:05)
-        );
-        assert_parsed!(
-            third::pdt_iteration("2019-01-02 05:10:20"),
-            datetime!(2019-01-02 05:10:20)
-        );
-        assert_parsed!(
-            third::pdt_iteration("2018-12-01 04:09:19.543"),
-            datetime!(2018-12-01 04:09:19.543)
-        );
-        assert_parsed!(
-            third::pdt_iteration("2017-11-30 03:08"),
-            datetime!(2017-11-30 03:08)
-        );
-        assert_parsed!(
-            third::pdt_iteration("2016-10-29T02:07:17"),
-            datetime!(2016-10-29 02:07:17)
-        );
-        assert_parsed!(
-            third::pdt_iteration("2015-09-28T01:06:16.432"),
-            datetime!(2015-09-28 01:06:16.432)
-        );
-        assert_parsed!(
-            third::pdt_iteration("2012-06-25 22:03:13.321Z"),
-            datetime!(2012-06-25 22:03:13.321)
-        );
-        assert_parsed!(
-            third::pdt_iteration("2009-03-22T19:00:10.21Z"),
-            datetime!(2009-03-22 19:00:10.21)
-        );
-        assert_parsed!(
-            third::pdt_iteration("2013-07-26 23:04:14Z"),
-            datetime!(2013-07-26 23:04:14)
-        );
-        assert_parsed!(
-            third::pdt_iteration("2011-05-24 21:02Z"),
-            datetime!(2011-05-24 21:02)
-        );
-        assert_parsed!(
-            third::pdt_iteration("2010-04-23T20:01:11Z"),
-            datetime!(2010-04-23 20:01:11)
-        );
-        assert_parsed!(
-            third::pdt_iteration("2008-02-21T18:59Z"),
-            datetime!(2008-02-21 18:59)
-        );
-    }
-
-    #[test]
-    fn test_pdt_fourth_iteration() {
-        assert_parsed!(
-            fourth::pdt_iteration("2014-08-27T00:05"),
-            datetime!(2014-08-27 00:05)
-        );
-        assert_parsed!(
-            fourth::pdt_iteration("2019-01-02 05:10:20"),
-            datetime!(2019-01-02 05:10:20)
-        );
-        assert_parsed!(
-            fourth::pdt_iteration("2018-12-01 04:09:19.543"),
-            datetime!(2018-12-01 04:09:19.543)
-        );
-        assert_parsed!(
-            fourth::pdt_iteration("2017-11-30 03:08"),
-            datetime!(2017-11-30 03:08)
-        );
-        assert_parsed!(
-            fourth::pdt_iteration("2016-10-29T02:07:17"),
-            datetime!(2016-10-29 02:07:17)
-        );
-        assert_parsed!(
-            fourth::pdt_iteration("2015-09-28T01:06:16.432"),
-            datetime!(2015-09-28 01:06:16.432)
-        );
-        assert_parsed!(
-            fourth::pdt_iteration("2012-06-25 22:03:13.321Z"),
-            datetime!(2012-06-25 22:03:13.321)
-        );
-        assert_parsed!(
-            fourth::pdt_iteration("2009-03-22T19:00:10.21Z"),
-            datetime!(2009-03-22 19:00:10.21)
-        );
-        assert_parsed!(
-            fourth::pdt_iteration("2013-07-26 23:04:14Z"),
-            datetime!(2013-07-26 23:04:14)
-        );
-        assert_parsed!(
-            fourth::pdt_iteration("2011-05-24 21:02Z"),
-            datetime!(2011-05-24 21:02)
-        );
-        assert_parsed!(
-            fourth::pdt_iteration("2010-04-23T20:01:11Z"),
-            datetime!(2010-04-23 20:01:11)
-        );
-        assert_parsed!(
-            fourth::pdt_iteration("2008-02-21T18:59Z"),
-            datetime!(2008-02-21 18:59)
-        );
-    }
-
-    #[test]
-    fn test_time_first_iteration() {
-        assert_parsed!(first::time_iteration("21:46:32"), time!(21:46:32));
-        assert_parsed!(first::time_iteration("20:45:31.133"), time!(20:45:31.133));
-        assert_parsed!(first::time_iteration("19:44"), time!(19:44));
-    }
-
-    #[test]
-    fn test_time_second_iteration() {
-        assert_parsed!(second::time_iteration("21:46:32"), time!(21:46:32));
-        assert_parsed!(second::time_iteration("20:45:31.133"), time!(20:45:31.133));
-        assert_parsed!(second::time_iteration("19:44"), time!(19:44));
-    }
-
-    #[test]
-    fn test_time_third_iteration() {
-        assert_parsed!(third::time_iteration("21:46:32"), time!(21:46:32));
-        assert_parsed!(third::time_iteration("20:45:31.133"), time!(20:45:31.133));
-        assert_parsed!(third::time_iteration("19:44"), time!(19:44));
-    }
-}
@@ -0,0 +1,295 @@
+//! Which of SQLite's time value formats a decoded value was written in.
+
+use super::walk::Walker;
+use super::Candidate;
+use time::error::TryFromParsed;
+use time::format_description::well_known::Rfc3339;
+use time::parsing::Parsed;
+use time::OffsetDateTime;
+
+/// The shape of the text a value was decoded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MatchedFormat {
+    /// What separated the date from the time, or `None` for a time on its own.
+    pub separator: Option<Separator>,
+    pub precision: Precision,
+    pub offset: OffsetStyle,
+}
+
+/// The characters between the date and the time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Separator {
+    Space,
+    T,
+    /// The time follows the date directly, which `fourth` tolerates.
+    None,
+    /// A space followed by a `T`, which `fourth` tolerates.
+    SpaceAndT,
+}
+
+/// The smallest unit of time that was written out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Precision {
+    Minutes,
+    Seconds,
+    /// Fractional seconds with this many digits, or `u8::MAX` for that many or more.
+    Subseconds(u8),
+}
+
+/// How the offset from UTC was written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OffsetStyle {
+    None,
+    /// A trailing `Z`.
+    Zulu,
+    /// A signed hour, optionally followed by minutes with or without a `:` between them.
+    Numeric {
+        colon: bool,
+        minutes: bool,
+    },
+}
+
+impl MatchedFormat {
+    /// The number SQLite's documentation gives this format, from 2 (`YYYY-MM-DD HH:MM`) to 10
+    /// (`HH:MM:SS.SSS`). Shapes SQLite does not list, like a missing separator, have none.
+    ///
+    /// SQLite lists its formats without an offset and allows one to be added to any of them, so
+    /// the offset does not change the number.
+    pub fn sqlite_format(&self) -> Option<u8> {
+        let base = match self.separator {
+            Some(Separator::Space) => 2,
+            Some(Separator::T) => 5,
+            None => 8,
+            Some(Separator::None | Separator::SpaceAndT) => return None,
+        };
+        let precision = match self.precision {
+            Precision::Minutes => 0,
+            Precision::Seconds => 1,
+            Precision::Subseconds(_) => 2,
+        };
+
+        Some(base + precision)
+    }
+
+    /// Reads the format off a walk that matched all of `input`, using where each component
+    /// started rather than parsing the input again.
+    pub(crate) fn from_walk(input: &[u8], walker: &Walker<'_>) -> Self {
+        let separator = walker.start_of("day").map(|day| {
+            let hour = walker.start_of("hour").unwrap_or(input.len());
+            match &input[day + 2..hour] {
+                b" " => Separator::Space,
+                b"T" | b"t" => Separator::T,
+                b"" => Separator::None,
+                _ => Separator::SpaceAndT,
+            }
+        });
+
+        let precision = match (walker.start_of("second"), walker.start_of("subsecond")) {
+            (_, Some(subsecond)) => Precision::Subseconds(digits_from(input, subsecond)),
+            (Some(_), None) => Precision::Seconds,
+            (None, None) => Precision::Minutes,
+        };
+
+        let offset = match walker.start_of("offset hour") {
+            Some(hour) => Self::numeric_offset(&input[hour..]),
+            None if matches!(input.last(), Some(b'Z' | b'z')) => OffsetStyle::Zulu,
+            None => OffsetStyle::None,
+        };
+
+        Self {
+            separator,
+            precision,
+            offset,
+        }
+    }
+
+    /// Reads the format off text that `well_known::Rfc3339` accepted, whose layout is fixed up to
+    /// the subsecond.
+    pub(crate) fn from_rfc3339(input: &[u8]) -> Self {
+        let precision = match input.get(19) {
+            Some(b'.') => Precision::Subseconds(digits_from(input, 20)),
+            _ => Precision::Seconds,
+        };
+        let offset = match input.last() {
+            Some(b'Z' | b'z') => OffsetStyle::Zulu,
+            _ => OffsetStyle::Numeric {
+                colon: true,
+                minutes: true,
+            },
+        };
+
+        Self {
+            separator: Some(Separator::T),
+            precision,
+            offset,
+        }
+    }
+
+    fn numeric_offset(offset: &[u8]) -> OffsetStyle {
+        // The sign and two hour digits come first
+        let rest = offset.get(3..).unwrap_or_default();
+        OffsetStyle::Numeric {
+            colon: rest.first() == Some(&b':'),
+            minutes: rest.iter().any(u8::is_ascii_digit),
+        }
+    }
+}
+
+/// Tries each of `candidates` in turn, as the iteration they belong to does, and returns the first
+/// value one of them decodes with the format read off that same walk.
+pub(crate) fn decode<T>(input: &str, candidates: &[Candidate<'_>]) -> Option<(T, MatchedFormat)>
+where
+    T: TryFrom<Parsed, Error = TryFromParsed>,
+{
+    candidates
+        .iter()
+        .find_map(|candidate| walk(input.as_bytes(), candidate))
+}
+
+/// Like [`decode`], but decoding the candidate named `RFC 3339` with `well_known::Rfc3339`, which
+/// its items only stand in for.
+pub(crate) fn decode_odt(
+    input: &str,
+    candidates: &[Candidate<'_>],
+) -> Option<(OffsetDateTime, MatchedFormat)> {
+    candidates.iter().find_map(|candidate| {
+        if candidate.name == "RFC 3339" {
+            let dt = OffsetDateTime::parse(input, &Rfc3339).ok()?;
+            return Some((dt, MatchedFormat::from_rfc3339(input.as_bytes())));
+        }
+        walk(input.as_bytes(), candidate)
+    })
+}
+
+fn walk<T>(input: &[u8], candidate: &Candidate<'_>) -> Option<(T, MatchedFormat)>
+where
+    T: TryFrom<Parsed, Error = TryFromParsed>,
+{
+    let mut walker = Walker::new(input);
+    if !(walker.items(candidate.items) && walker.is_done()) {
+        return None;
+    }
+    let value = T::try_from(walker.parsed).ok()?;
+    Some((value, MatchedFormat::from_walk(input, &walker)))
+}
+
+fn digits_from(input: &[u8], start: usize) -> u8 {
+    let count = input[start..]
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count();
+    u8::try_from(count).unwrap_or(u8::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fourth;
+
+    fn odt_format(input: &str) -> MatchedFormat {
+        let (value, format) = fourth::odt_iteration_with_format(input).unwrap();
+        assert_eq!(value, fourth::odt_iteration(input).unwrap());
+        format
+    }
+
+    fn pdt_format(input: &str) -> MatchedFormat {
+        let (value, format) = fourth::pdt_iteration_with_format(input).unwrap();
+        assert_eq!(value, fourth::pdt_iteration(input).unwrap());
+        format
+    }
+
+    const HOURS_MINUTES: OffsetStyle = OffsetStyle::Numeric {
+        colon: true,
+        minutes: true,
+    };
+
+    #[test]
+    fn test_odt_with_format() {
+        let format = odt_format("2016-03-07T22:36:55.135+03:30");
+        assert_eq!(
+            format,
+            MatchedFormat {
+                separator: Some(Separator::T),
+                precision: Precision::Subseconds(3),
+                offset: HOURS_MINUTES,
+            }
+        );
+        assert_eq!(format.sqlite_format(), Some(7));
+
+        let format = odt_format("2015-11-19 01:01:39+01:00");
+        assert_eq!(format.separator, Some(Separator::Space));
+        assert_eq!(format.precision, Precision::Seconds);
+        assert_eq!(format.sqlite_format(), Some(3));
+
+        let format = odt_format("2013-09-17 23:59-01:00");
+        assert_eq!(format.precision, Precision::Minutes);
+        assert_eq!(format.sqlite_format(), Some(2));
+
+        let format = odt_format("2016-03-07T22:36:55Z");
+        assert_eq!(format.offset, OffsetStyle::Zulu);
+        assert_eq!(format.sqlite_format(), Some(6));
+
+        let format = odt_format("2016-03-0722:36+05");
+        assert_eq!(format.separator, Some(Separator::None));
+        assert_eq!(
+            format.offset,
+            OffsetStyle::Numeric {
+                colon: false,
+                minutes: false,
+            }
+        );
+        assert_eq!(format.sqlite_format(), None);
+
+        let digits = "5".repeat(300);
+        let format = odt_format(&format!("2016-03-07T22:36:55.{digits}+03:30"));
+        assert_eq!(format.precision, Precision::Subseconds(u8::MAX));
+    }
+
+    #[test]
+    fn test_pdt_with_format() {
+        let format = pdt_format("2011-05-24 21:02Z");
+        assert_eq!(
+            format,
+            MatchedFormat {
+                separator: Some(Separator::Space),
+                precision: Precision::Minutes,
+                offset: OffsetStyle::Zulu,
+            }
+        );
+        assert_eq!(pdt_format("2014-08-27T00:05").sqlite_format(), Some(5));
+        assert_eq!(
+            pdt_format("2009-03-22T19:00:10.21Z").precision,
+            Precision::Subseconds(2)
+        );
+        assert_eq!(
+            pdt_format("2017-11-30 T03:08").separator,
+            Some(Separator::SpaceAndT)
+        );
+    }
+
+    #[test]
+    fn test_time_with_format() {
+        let (time, format) = fourth::time_iteration_with_format("20:45:31.133").unwrap();
+        assert_eq!(time, fourth::time_iteration("20:45:31.133").unwrap());
+        assert_eq!(
+            format,
+            MatchedFormat {
+                separator: None,
+                precision: Precision::Subseconds(3),
+                offset: OffsetStyle::None,
+            }
+        );
+        assert_eq!(format.sqlite_format(), Some(10));
+
+        let (_, format) = fourth::time_iteration_with_format("19:44").unwrap();
+        assert_eq!(format.sqlite_format(), Some(8));
+    }
+
+    #[test]
+    fn test_with_format_errors() {
+        let error = fourth::odt_iteration_with_format("2013-09-17 23:59x").unwrap_err();
+        assert_eq!(error.furthest().unwrap().position(), 16);
+        assert!(fourth::pdt_iteration_with_format("2016-02-30 10:00").is_err());
+        assert!(fourth::time_iteration_with_format("24:00").is_err());
+    }
+}
@@ -0,0 +1,948 @@
+//! Named, documented languages of text a decoder accepts.
+//!
+//! The iteration strategies predate the profiles and keep the languages their format
+//! descriptions happen to accept, so that they can still be benchmarked against each other.
+//! `first` and `second` try lists of the layouts SQLite documents. `third` and `fourth` make the
+//! punctuation around seconds and offsets optional, so they also accept text like `10:00:`,
+//! `10:0005` or `+05`, and `fourth` also allows nothing, or a space and a `T`, between the date
+//! and the time. None of them normalise out of range values or accept Julian day numbers.
+//!
+//! A profile holds a decoder to a language that is written down. A strategy is limited to one
+//! with [`with_profile`](SqliteTemporalParser::with_profile), and a
+//! [`FallbackChain`](crate::chain::FallbackChain) or [`ParserConfig`](crate::config::ParserConfig)
+//! is given one when it is made. The limited decoder still returns the value it decoded itself;
+//! the profile only decides which text gets that far.
+//!
+//! Not every decoder takes a profile argument. The iteration functions, such as
+//! [`fourth::odt_iteration`](crate::fourth::odt_iteration) and
+//! [`fourth::odt_iteration_with_format`](crate::fourth::odt_iteration_with_format), take only the
+//! text and keep their own languages, because they are what the benchmarks time and compare. To
+//! hold one to a profile, go through its strategy type, as in `Fourth.with_profile(profile)`.
+
+use super::error::Attempt;
+use super::formats::*;
+use super::matched::MatchedFormat;
+use super::walk::Walker;
+use super::{Candidate, Error, FormatReportingParser, Problem, SqliteTemporalParser};
+use time::error::TryFromParsed;
+use time::parsing::Parsed;
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+/// The Julian day, in milliseconds, of `9999-12-31 23:59:59.999`, the last instant SQLite accepts.
+const MAX_JULIAN_DAY_MILLIS: i64 = 464_269_060_799_999;
+
+/// The first Julian day number SQLite does not accept, `10000-01-01 00:00`. Numbers from `0` up to
+/// it are Julian days, both in the grammar and for SQLite's `auto` modifier.
+pub(crate) const MAX_JULIAN_DAY: f64 = 5373484.5;
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+const NANOS_PER_DAY: i64 = 86_400_000_000_000;
+
+/// A named language of accepted text and the rules for turning it into a value.
+///
+/// `Profile` implements [`SqliteTemporalParser`], so a profile is passed wherever a decoder is
+/// chosen. The grammars below use ABNF, where `DIGIT` is an ASCII digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Profile {
+    /// Only the canonical format chosen by the `default-format-*` features, which is what the
+    /// [`encode`](crate::encode) functions write.
+    ///
+    /// ```text
+    /// offset-date-time    = date separator time offset
+    /// primitive-date-time = date separator time
+    /// time                = 2DIGIT ":" 2DIGIT ":" 2DIGIT "." fraction
+    /// date                = 4DIGIT "-" 2DIGIT "-" 2DIGIT
+    /// separator           = " "      ; "T" with default-format-iso8601-t
+    /// fraction            = 1*DIGIT  ; 3DIGIT with default-format-millis
+    /// offset              = ("+" / "-") 2DIGIT ":" 2DIGIT
+    /// ```
+    ///
+    /// Every field must be in range: February 30 and hour 24 are rejected.
+    Strict,
+    /// Exactly the text SQLite's date and time functions accept, decoded to the instant
+    /// `julianday()` computes for it.
+    ///
+    /// ```text
+    /// value     = date separator [time zone] / time zone / "now" / number
+    /// date      = ["-"] 4DIGIT "-" 2DIGIT "-" 2DIGIT                ; month 01-12, day 01-31
+    /// separator = *(space / "T")
+    /// time      = 2DIGIT ":" 2DIGIT [":" 2DIGIT ["." 1*DIGIT]]    ; hour 00-24
+    /// zone      = *space [("Z" / "z" / offset) *space]
+    /// offset    = ("+" / "-") 2DIGIT ":" 2DIGIT                     ; hour 00-14
+    /// number    = *space decimal *space                             ; 0 to 5373484.5, exclusive
+    /// space     = " " / %x09-0D
+    /// ```
+    ///
+    /// `"now"` is matched case-insensitively and a `number` is a Julian day. Minutes and seconds
+    /// run from 00 to 59. As in SQLite, days past the end of the month roll over into the next
+    /// one, so February 30 is in March, and hour 24 is midnight of the next day. Fractional
+    /// seconds are clamped to `.999` and rounded to the millisecond. A time without a date is on
+    /// 2000-01-01. The offset is subtracted to give UTC, and instants before
+    /// `-4713-11-24 12:00` or after `9999-12-31 23:59:59.999` are rejected.
+    ///
+    /// Offset date-times keep the offset they were written with, or UTC if there was none. The
+    /// other types are in UTC, so a date or time is the one `date()` or `time()` would return,
+    /// except that SQLite writes a time of hour 24 as given, such as `24:30:00`, where this
+    /// returns the time on the next day. `julianday()` agrees with this in every case.
+    SqliteCompatible,
+    /// [`SqliteCompatible`](Profile::SqliteCompatible) with more whitespace, lowercase letters and
+    /// offset forms, keeping fractional seconds to the nanosecond.
+    ///
+    /// ```text
+    /// value     = *space (date separator [time zone] / time zone / "now" / number)
+    /// separator = *(space / "T" / "t")
+    /// offset    = ("+" / "-") 2DIGIT [[":"] 2DIGIT]                 ; hour 00-23
+    /// ```
+    ///
+    /// Rules not listed are as for `SqliteCompatible`. Digits past the ninth of a fraction are
+    /// ignored rather than rounded.
+    Permissive,
+}
+
+impl Profile {
+    fn name(self) -> &'static str {
+        match self {
+            Profile::Strict => "strict",
+            Profile::SqliteCompatible => "SQLite",
+            Profile::Permissive => "permissive",
+        }
+    }
+
+    /// Checks that `input` is in this profile's language for a `T`, without building the value.
+    /// For the lenient profiles that is any text they can resolve to an instant.
+    pub(crate) fn admit<T: Canonical>(self, input: &str) -> Result<(), Error> {
+        if self == Profile::Strict {
+            let mut walker = Walker::new(input.as_bytes());
+            let matched = walker.items(T::STRICT[0].items) && walker.is_done();
+            if matched && T::try_from(walker.parsed).is_ok() {
+                return Ok(());
+            }
+            return Err(Error::diagnose::<T>(input, T::STRICT));
+        }
+
+        let value = self.scan(input)?;
+        self.instant_of(input, &value).map(drop)
+    }
+
+    /// Decodes any of the grammar's values to the instant it stands for, in UTC, and the offset
+    /// it was written with.
+    fn instant(self, input: &str) -> Result<(PrimitiveDateTime, UtcOffset), Error> {
+        let value = self.scan(input)?;
+        self.instant_of(input, &value)
+    }
+
+    /// Reads `input` with this profile's grammar, without resolving it to an instant. Not for
+    /// `Strict`, whose grammar is a set of format descriptions.
+    pub(crate) fn scan(self, input: &str) -> Result<Value<'_>, Error> {
+        let mut scanner = Scanner::new(input, self == Profile::Permissive);
+        scanner
+            .value()
+            .ok_or_else(|| Error::from_attempt(input, scanner.attempt(self.name())))
+    }
+
+    /// The instant a scanned value stands for, in UTC, and the offset it was written with.
+    pub(crate) fn instant_of(
+        self,
+        input: &str,
+        value: &Value<'_>,
+    ) -> Result<(PrimitiveDateTime, UtcOffset), Error> {
+        let out_of_range = || {
+            let attempt = Attempt::at(
+                input,
+                self.name(),
+                0,
+                Problem::OutOfRange { component: "year" },
+            );
+            Error::from_attempt(input, attempt)
+        };
+        match value {
+            Value::Text(fields) => self.resolve(fields).ok_or_else(out_of_range),
+            Value::JulianDay(day) if (0.0..MAX_JULIAN_DAY).contains(day) => {
+                let millis = (day * MILLIS_PER_DAY as f64 + 0.5) as i64;
+                from_julian_day_millis(millis)
+                    .map(|utc| (utc, UtcOffset::UTC))
+                    .ok_or_else(out_of_range)
+            }
+            Value::JulianDay(_) => Err(out_of_range()),
+            Value::Now => {
+                let now = OffsetDateTime::now_utc();
+                let utc = PrimitiveDateTime::new(now.date(), now.time());
+                match self {
+                    // SQLite's clock, like the rest of its times, counts milliseconds
+                    Profile::SqliteCompatible => {
+                        let millis = now.millisecond();
+                        let utc = utc.replace_millisecond(millis).unwrap_or(utc);
+                        Ok((utc, UtcOffset::UTC))
+                    }
+                    _ => Ok((utc, UtcOffset::UTC)),
+                }
+            }
+        }
+    }
+
+    /// Turns the scanned fields into an instant, normalising out of range days and hours.
+    fn resolve(self, fields: &Fields<'_>) -> Option<(PrimitiveDateTime, UtcOffset)> {
+        let (year, month, day) = fields.date.unwrap_or((2000, 1, 1));
+        let clock = fields.clock.unwrap_or_default();
+        let offset_minutes = match fields.zone {
+            Zone::Offset { minutes, .. } => minutes,
+            Zone::None | Zone::Zulu => 0,
+        };
+        let offset = UtcOffset::from_whole_seconds(offset_minutes * 60).ok()?;
+
+        let month = Month::try_from(month).ok()?;
+        let julian_day = Date::from_calendar_date(year, month, 1)
+            .ok()?
+            .to_julian_day() as i64
+            + day as i64
+            - 1;
+        let minutes = clock.hour as i64 * 60 + clock.minute as i64 - offset_minutes as i64;
+
+        let utc = if self == Profile::SqliteCompatible {
+            if year < -4713 {
+                return None;
+            }
+            // The same floating point steps as `parseHhMmSs` and `computeJD` in SQLite's `date.c`
+            let mut fraction = 0.0;
+            let mut scale = 1.0;
+            for digit in clock.fraction {
+                fraction = fraction * 10.0 + (digit - b'0') as f64;
+                scale *= 10.0;
+            }
+            fraction /= scale;
+            if fraction > 0.999 {
+                fraction = 0.999;
+            }
+            let seconds = clock.second as f64 + fraction;
+
+            let millis = julian_day * MILLIS_PER_DAY - MILLIS_PER_DAY / 2
+                + minutes * 60_000
+                + (seconds * 1000.0 + 0.5) as i64;
+            if !(0..=MAX_JULIAN_DAY_MILLIS).contains(&millis) {
+                return None;
+            }
+            from_julian_day_millis(millis)?
+        } else {
+            let mut nanosecond = 0;
+            for position in 0..9 {
+                let digit = clock.fraction.get(position).map_or(0, |digit| digit - b'0');
+                nanosecond = nanosecond * 10 + digit as i64;
+            }
+            let nanos = minutes * 60_000_000_000 + clock.second as i64 * 1_000_000_000 + nanosecond;
+
+            let date = Date::from_julian_day(
+                (julian_day + nanos.div_euclid(NANOS_PER_DAY))
+                    .try_into()
+                    .ok()?,
+            )
+            .ok()?;
+            let nanos = nanos.rem_euclid(NANOS_PER_DAY);
+            let time = Time::from_hms_nano(
+                (nanos / 3_600_000_000_000) as u8,
+                (nanos / 60_000_000_000 % 60) as u8,
+                (nanos / 1_000_000_000 % 60) as u8,
+                (nanos % 1_000_000_000) as u32,
+            )
+            .ok()?;
+            PrimitiveDateTime::new(date, time)
+        };
+
+        Some((utc, offset))
+    }
+}
+
+/// Converts SQLite's integer Julian day milliseconds, which count from noon, to a date-time.
+fn from_julian_day_millis(millis: i64) -> Option<PrimitiveDateTime> {
+    let millis = millis + MILLIS_PER_DAY / 2;
+    let date = Date::from_julian_day(millis.div_euclid(MILLIS_PER_DAY).try_into().ok()?).ok()?;
+    let millis = millis.rem_euclid(MILLIS_PER_DAY);
+    let time = Time::from_hms_milli(
+        (millis / 3_600_000) as u8,
+        (millis / 60_000 % 60) as u8,
+        (millis / 1000 % 60) as u8,
+        (millis % 1000) as u16,
+    )
+    .ok()?;
+
+    Some(PrimitiveDateTime::new(date, time))
+}
+
+impl SqliteTemporalParser for Profile {
+    fn parse_offset_date_time(&self, input: &str) -> Result<OffsetDateTime, Error> {
+        if *self == Profile::Strict {
+            return OffsetDateTime::parse(input, DEFAULT_OFFSET_DATE_TIME)
+                .map_err(|_| Error::diagnose::<OffsetDateTime>(input, STRICT_ODT_CANDIDATES));
+        }
+
+        let (utc, offset) = self.instant(input)?;
+        let utc = utc.assume_utc();
+        Ok(utc.checked_to_offset(offset).unwrap_or(utc))
+    }
+
+    fn parse_primitive_date_time(&self, input: &str) -> Result<PrimitiveDateTime, Error> {
+        if *self == Profile::Strict {
+            return PrimitiveDateTime::parse(input, DEFAULT_PRIMITIVE_DATE_TIME)
+                .map_err(|_| Error::diagnose::<PrimitiveDateTime>(input, STRICT_PDT_CANDIDATES));
+        }
+
+        self.instant(input).map(|(utc, _)| utc)
+    }
+
+    fn parse_time(&self, input: &str) -> Result<Time, Error> {
+        if *self == Profile::Strict {
+            return Time::parse(input, DEFAULT_TIME)
+                .map_err(|_| Error::diagnose::<Time>(input, STRICT_TIME_CANDIDATES));
+        }
+
+        self.instant(input).map(|(utc, _)| utc.time())
+    }
+
+    fn parse_date(&self, input: &str) -> Result<Date, Error> {
+        if *self == Profile::Strict {
+            return Date::parse(input, DATE)
+                .map_err(|_| Error::diagnose::<Date>(input, STRICT_DATE_CANDIDATES));
+        }
+
+        self.instant(input).map(|(utc, _)| utc.date())
+    }
+}
+
+/// A decoder limited to a profile, made by
+/// [`with_profile`](SqliteTemporalParser::with_profile).
+///
+/// Text is accepted if both the decoder and the profile accept it, and the value is the one the
+/// decoder returned, so the profile only narrows the language. The profile checks the text
+/// without decoding it again. The error is the decoder's if the decoder rejects the text, and the
+/// profile's otherwise.
+///
+/// ```
+/// use time_tests::profile::Profile;
+/// use time_tests::{Fourth, SqliteTemporalParser};
+/// use time::macros::time;
+///
+/// let fourth = Fourth.with_profile(Profile::SqliteCompatible);
+/// // Decoded by `fourth`, to the nanosecond, where SQLite would round to the millisecond
+/// assert_eq!(fourth.parse_time("20:45:31.1339").unwrap(), time!(20:45:31.1339));
+/// // `fourth` alone accepts this, but SQLite needs the colon
+/// assert!(fourth.parse_time("10:0005").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Profiled<D> {
+    decoder: D,
+    profile: Profile,
+}
+
+impl<D> Profiled<D> {
+    pub fn new(decoder: D, profile: Profile) -> Self {
+        Self { decoder, profile }
+    }
+
+    pub fn profile(&self) -> Profile {
+        self.profile
+    }
+}
+
+impl<D: FormatReportingParser> FormatReportingParser for Profiled<D> {
+    fn parse_offset_date_time_with_format(
+        &self,
+        input: &str,
+    ) -> Result<(OffsetDateTime, MatchedFormat), Error> {
+        let decoded = self.decoder.parse_offset_date_time_with_format(input)?;
+        self.profile.admit::<OffsetDateTime>(input)?;
+        Ok(decoded)
+    }
+
+    fn parse_primitive_date_time_with_format(
+        &self,
+        input: &str,
+    ) -> Result<(PrimitiveDateTime, MatchedFormat), Error> {
+        let decoded = self.decoder.parse_primitive_date_time_with_format(input)?;
+        self.profile.admit::<PrimitiveDateTime>(input)?;
+        Ok(decoded)
+    }
+
+    fn parse_time_with_format(&self, input: &str) -> Result<(Time, MatchedFormat), Error> {
+        let decoded = self.decoder.parse_time_with_format(input)?;
+        self.profile.admit::<Time>(input)?;
+        Ok(decoded)
+    }
+}
+
+impl<D: SqliteTemporalParser> SqliteTemporalParser for Profiled<D> {
+    fn parse_offset_date_time(&self, input: &str) -> Result<OffsetDateTime, Error> {
+        let decoded = self.decoder.parse_offset_date_time(input)?;
+        self.profile.admit::<OffsetDateTime>(input)?;
+        Ok(decoded)
+    }
+
+    fn parse_primitive_date_time(&self, input: &str) -> Result<PrimitiveDateTime, Error> {
+        let decoded = self.decoder.parse_primitive_date_time(input)?;
+        self.profile.admit::<PrimitiveDateTime>(input)?;
+        Ok(decoded)
+    }
+
+    fn parse_time(&self, input: &str) -> Result<Time, Error> {
+        let decoded = self.decoder.parse_time(input)?;
+        self.profile.admit::<Time>(input)?;
+        Ok(decoded)
+    }
+
+    fn parse_date(&self, input: &str) -> Result<Date, Error> {
+        let decoded = self.decoder.parse_date(input)?;
+        self.profile.admit::<Date>(input)?;
+        Ok(decoded)
+    }
+}
+
+/// Why `input` is in none of the strict formats, tried as the type each one decodes to.
+pub(crate) fn strict_error(input: &str) -> Error {
+    Error::diagnose::<Date>(input, STRICT_DATE_CANDIDATES)
+        .and(Error::diagnose::<Time>(input, STRICT_TIME_CANDIDATES))
+        .and(Error::diagnose::<PrimitiveDateTime>(
+            input,
+            STRICT_PDT_CANDIDATES,
+        ))
+        .and(Error::diagnose::<OffsetDateTime>(
+            input,
+            STRICT_ODT_CANDIDATES,
+        ))
+}
+
+/// The types a profile decodes to, with the canonical format [`Profile::Strict`] holds each to.
+pub(crate) trait Canonical: TryFrom<Parsed, Error = TryFromParsed> {
+    const STRICT: &'static [Candidate<'static>];
+}
+
+impl Canonical for OffsetDateTime {
+    const STRICT: &'static [Candidate<'static>] = STRICT_ODT_CANDIDATES;
+}
+
+impl Canonical for PrimitiveDateTime {
+    const STRICT: &'static [Candidate<'static>] = STRICT_PDT_CANDIDATES;
+}
+
+impl Canonical for Time {
+    const STRICT: &'static [Candidate<'static>] = STRICT_TIME_CANDIDATES;
+}
+
+impl Canonical for Date {
+    const STRICT: &'static [Candidate<'static>] = STRICT_DATE_CANDIDATES;
+}
+
+const STRICT_ODT_CANDIDATES: &[Candidate] = &[Candidate {
+    name: "DEFAULT_OFFSET_DATE_TIME",
+    items: DEFAULT_OFFSET_DATE_TIME,
+}];
+
+const STRICT_PDT_CANDIDATES: &[Candidate] = &[Candidate {
+    name: "DEFAULT_PRIMITIVE_DATE_TIME",
+    items: DEFAULT_PRIMITIVE_DATE_TIME,
+}];
+
+const STRICT_TIME_CANDIDATES: &[Candidate] = &[Candidate {
+    name: "DEFAULT_TIME",
+    items: DEFAULT_TIME,
+}];
+
+const STRICT_DATE_CANDIDATES: &[Candidate] = &[Candidate {
+    name: "DATE",
+    items: DATE,
+}];
+
+/// What a [`Scanner`] read, before any normalisation.
+pub(crate) enum Value<'a> {
+    Text(Fields<'a>),
+    JulianDay(f64),
+    Now,
+}
+
+pub(crate) struct Fields<'a> {
+    /// Year, month and day, with the day not yet checked against the month.
+    pub(crate) date: Option<(i32, u8, u8)>,
+    pub(crate) clock: Option<Clock<'a>>,
+    pub(crate) zone: Zone,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Clock<'a> {
+    /// From 0 to 24.
+    pub(crate) hour: u8,
+    pub(crate) minute: u8,
+    pub(crate) second: u8,
+    /// The digits after the decimal point, if any.
+    pub(crate) fraction: &'a [u8],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Zone {
+    None,
+    Zulu,
+    Offset { minutes: i32 },
+}
+
+/// A hand-written reader for the grammars of the lenient profiles, modelled on `parseDateOrTime`
+/// in SQLite's `date.c`. Like the [`Walker`](crate::walk::Walker), it remembers the furthest
+/// point the input stopped matching.
+pub(crate) struct Scanner<'a> {
+    input: &'a [u8],
+    position: usize,
+    permissive: bool,
+    /// The name of the last field or literal read.
+    last: Option<&'static str>,
+    stopped: Option<(usize, Problem)>,
+}
+
+impl<'a> Scanner<'a> {
+    pub(crate) fn new(input: &'a str, permissive: bool) -> Self {
+        Self {
+            input: input.as_bytes(),
+            position: 0,
+            permissive,
+            last: None,
+            stopped: None,
+        }
+    }
+
+    /// Reads a whole value, trying a date first, then a time, `now` and a number.
+    pub(crate) fn value(&mut self) -> Option<Value<'a>> {
+        if self.permissive {
+            self.spaces();
+        }
+        let start = self.position;
+
+        if let Some(fields) = self.date_and_time() {
+            return Some(Value::Text(fields));
+        }
+        self.reset(start);
+        if let Some((clock, zone)) = self.clock_and_zone() {
+            let fields = Fields {
+                date: None,
+                clock: Some(clock),
+                zone,
+            };
+            return Some(Value::Text(fields));
+        }
+
+        let rest = &self.input[start..];
+        if rest.eq_ignore_ascii_case(b"now") {
+            return Some(Value::Now);
+        }
+        let number = rest.trim_ascii();
+        if number.iter().any(u8::is_ascii_digit)
+            && number
+                .iter()
+                .all(|byte| byte.is_ascii_digit() || b"+-.eE".contains(byte))
+        {
+            // Only digits and signs are left, so the text is ASCII
+            if let Ok(day) = std::str::from_utf8(number).unwrap_or_default().parse() {
+                return Some(Value::JulianDay(day));
+            }
+        }
+
+        None
+    }
+
+    /// The furthest point the input stopped matching, as an error attempt named `format`.
+    pub(crate) fn attempt(&self, format: &'static str) -> Attempt {
+        let input = std::str::from_utf8(self.input).unwrap_or_default();
+        match &self.stopped {
+            Some((position, problem)) => Attempt::at(input, format, *position, problem.clone()),
+            None => Attempt::at(input, format, 0, Problem::Rejected),
+        }
+    }
+
+    fn date_and_time(&mut self) -> Option<Fields<'a>> {
+        let negative = self.peek() == Some(b'-');
+        if negative {
+            self.position += 1;
+        }
+        let year = self.digits(4, "year", 0..=9999)? as i32;
+        self.literal(b'-')?;
+        let month = self.digits(2, "month", 1..=12)? as u8;
+        self.literal(b'-')?;
+        let day = self.digits(2, "day", 1..=31)? as u8;
+        let date = Some((if negative { -year } else { year }, month, day));
+
+        while let Some(byte) = self.peek() {
+            if !(is_space(byte) || byte == b'T' || self.permissive && byte == b't') {
+                break;
+            }
+            self.position += 1;
+        }
+
+        let separated = self.position;
+        let last = self.last;
+        if let Some((clock, zone)) = self.clock_and_zone() {
+            return Some(Fields {
+                date,
+                clock: Some(clock),
+                zone,
+            });
+        }
+        self.position = separated;
+        self.last = last;
+        if self.position == self.input.len() {
+            return Some(Fields {
+                date,
+                clock: None,
+                zone: Zone::None,
+            });
+        }
+
+        None
+    }
+
+    fn clock_and_zone(&mut self) -> Option<(Clock<'a>, Zone)> {
+        let hour = self.digits(2, "hour", 0..=24)? as u8;
+        self.literal(b':')?;
+        let minute = self.digits(2, "minute", 0..=59)? as u8;
+        let mut clock = Clock {
+            hour,
+            minute,
+            ..Clock::default()
+        };
+
+        if self.peek() == Some(b':') {
+            self.position += 1;
+            self.last = Some("`:`");
+            clock.second = self.digits(2, "second", 0..=59)? as u8;
+            if self.peek() == Some(b'.')
+                && self
+                    .input
+                    .get(self.position + 1)
+                    .is_some_and(u8::is_ascii_digit)
+            {
+                self.position += 1;
+                let start = self.position;
+                while self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+                    self.position += 1;
+                }
+                clock.fraction = &self.input[start..self.position];
+                self.last = Some("subsecond");
+            }
+        }
+
+        let zone = self.zone()?;
+        Some((clock, zone))
+    }
+
+    fn zone(&mut self) -> Option<Zone> {
+        self.spaces();
+        let sign = match self.peek() {
+            None => return Some(Zone::None),
+            Some(b'Z' | b'z') => {
+                self.position += 1;
+                self.last = Some("`Z`");
+                self.end()?;
+                return Some(Zone::Zulu);
+            }
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            Some(_) => {
+                return self.stop(Problem::TrailingCharacters {
+                    after: self.after(),
+                })
+            }
+        };
+        self.position += 1;
+
+        let max_hour = if self.permissive { 23 } else { 14 };
+        let hours = self.digits(2, "offset hour", 0..=max_hour)? as i32;
+        let minutes = if self.peek() == Some(b':') || !self.permissive {
+            self.literal(b':')?;
+            self.digits(2, "offset minute", 0..=59)?
+        } else if self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+            self.digits(2, "offset minute", 0..=59)?
+        } else {
+            0
+        } as i32;
+
+        self.end()?;
+        Some(Zone::Offset {
+            minutes: sign * (hours * 60 + minutes),
+        })
+    }
+
+    /// Skips trailing whitespace, which must then be all that is left.
+    fn end(&mut self) -> Option<()> {
+        self.spaces();
+        if self.position == self.input.len() {
+            return Some(());
+        }
+
+        self.stop(Problem::TrailingCharacters {
+            after: self.after(),
+        })
+    }
+
+    fn digits(
+        &mut self,
+        count: usize,
+        name: &'static str,
+        range: std::ops::RangeInclusive<u32>,
+    ) -> Option<u32> {
+        let start = self.position;
+        let mut value = 0;
+        for _ in 0..count {
+            match self.peek() {
+                Some(byte) if byte.is_ascii_digit() => value = value * 10 + (byte - b'0') as u32,
+                _ => {
+                    return self.stop(Problem::Expected {
+                        expected: name.to_string(),
+                        after: self.after(),
+                    })
+                }
+            }
+            self.position += 1;
+        }
+
+        if !range.contains(&value) {
+            self.position = start;
+            return self.stop(Problem::OutOfRange { component: name });
+        }
+        self.last = Some(name);
+        Some(value)
+    }
+
+    fn literal(&mut self, literal: u8) -> Option<()> {
+        if self.peek() != Some(literal) {
+            return self.stop(Problem::Expected {
+                expected: format!("`{}`", literal as char),
+                after: self.after(),
+            });
+        }
+
+        self.position += 1;
+        self.last = Some(match literal {
+            b'-' => "`-`",
+            _ => "`:`",
+        });
+        Some(())
+    }
+
+    fn spaces(&mut self) {
+        while self.peek().is_some_and(is_space) {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn after(&self) -> Option<String> {
+        self.last.map(str::to_string)
+    }
+
+    fn reset(&mut self, position: usize) {
+        self.position = position;
+        self.last = None;
+    }
+
+    /// Remembers why the input stopped matching, unless it already got further. Out of range
+    /// fields count as matching everything, as they do for [`Attempt`]s.
+    fn stop<T>(&mut self, problem: Problem) -> Option<T> {
+        let progress = |position: usize, problem: &Problem| match problem {
+            Problem::OutOfRange { .. } => usize::MAX,
+            _ => position,
+        };
+        let further = self.stopped.as_ref().is_none_or(|(position, stopped)| {
+            progress(self.position, &problem) > progress(*position, stopped)
+        });
+        if further {
+            self.stopped = Some((self.position, problem));
+        }
+        None
+    }
+}
+
+/// Whether SQLite's `sqlite3Isspace` is true for `byte`.
+fn is_space(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t'..=b'\r')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{date, datetime, time};
+
+    #[test]
+    fn test_strict() {
+        let strict = Profile::Strict;
+        #[cfg(not(feature = "default-format-iso8601-t"))]
+        let (pdt, odt) = ("2018-12-01 04:09:19.543", "2014-10-18 00:00:38.697+00:00");
+        #[cfg(feature = "default-format-iso8601-t")]
+        let (pdt, odt) = ("2018-12-01T04:09:19.543", "2014-10-18T00:00:38.697+00:00");
+
+        assert_eq!(
+            strict.parse_primitive_date_time(pdt).unwrap(),
+            datetime!(2018-12-01 04:09:19.543)
+        );
+        assert_eq!(
+            strict.parse_offset_date_time(odt).unwrap(),
+            datetime!(2014-10-18 00:00:38.697 UTC)
+        );
+        assert_eq!(
+            strict.parse_time("20:45:31.133").unwrap(),
+            time!(20:45:31.133)
+        );
+        assert!(strict.parse_time("21:46:32").is_err());
+        assert!(strict.parse_date("2016-02-30").is_err());
+        assert!(strict
+            .parse_offset_date_time("2016-03-07T22:36:55Z")
+            .is_err());
+    }
+
+    #[test]
+    fn test_sqlite_compatible() {
+        // The instants SQLite 3.51 computes for these, as `julianday()` returns them
+        let sqlite = Profile::SqliteCompatible;
+        let pdt = |input| sqlite.parse_primitive_date_time(input).unwrap();
+        assert_eq!(pdt("2016-02-30 10:00"), datetime!(2016-03-01 10:00));
+        assert_eq!(pdt("2016-03-29 24:00"), datetime!(2016-03-30 00:00));
+        assert_eq!(pdt("2016-03-07T"), datetime!(2016-03-07 00:00));
+        assert_eq!(pdt("2016-03-07 T T10:00"), datetime!(2016-03-07 10:00));
+        assert_eq!(pdt("2016-03-0710:00"), datetime!(2016-03-07 10:00));
+        assert_eq!(pdt("2016-03-07 10:00 +01:00 "), datetime!(2016-03-07 09:00));
+        assert_eq!(
+            pdt("2016-03-07 10:00:00.5z"),
+            datetime!(2016-03-07 10:00:00.5)
+        );
+        assert_eq!(pdt("10:00+01:00"), datetime!(2000-01-01 09:00));
+        assert_eq!(pdt("-0001-03-01"), datetime!(-0001-03-01 00:00));
+        assert_eq!(pdt("2016"), datetime!(-4707-06-01 12:00));
+        assert_eq!(pdt(" 2457455.5 "), datetime!(2016-03-08 00:00));
+        assert_eq!(pdt("0000-01-01 00:00-01:00"), datetime!(0000-01-01 01:00));
+        assert_eq!(
+            pdt("9999-12-31 23:59:59.9999"),
+            datetime!(9999-12-31 23:59:59.999)
+        );
+        assert_eq!(
+            pdt("2016-03-07 10:00:00.123456789"),
+            datetime!(2016-03-07 10:00:00.123)
+        );
+
+        for rejected in [
+            " 2016-03-07",
+            "2016-03-07t10:00",
+            "2016-03-07 10:00+0100",
+            "2016-03-07 10:00+15:00",
+            "2016-03-07 10:00:00.",
+            "2016-03-07 10:60",
+            "-4714-01-01",
+            "5373484.5",
+            "9999-12-31 23:59:59-01:00",
+            "2016-13-01",
+        ] {
+            assert!(
+                sqlite.parse_primitive_date_time(rejected).is_err(),
+                "{rejected}"
+            );
+        }
+
+        assert_eq!(sqlite.parse_time("24:00").unwrap(), time!(0:00));
+        assert_eq!(sqlite.parse_date("24:00").unwrap(), date!(2000 - 01 - 02));
+        assert_eq!(
+            sqlite.parse_time("2016-03-07 22:36+01:00").unwrap(),
+            time!(21:36)
+        );
+        assert_eq!(
+            sqlite
+                .parse_offset_date_time("2016-03-07 22:36+01:00")
+                .unwrap()
+                .offset(),
+            UtcOffset::from_hms(1, 0, 0).unwrap()
+        );
+        assert_eq!(
+            sqlite.parse_offset_date_time("2016-03-07 22:36").unwrap(),
+            datetime!(2016-03-07 22:36 UTC)
+        );
+    }
+
+    #[test]
+    fn test_permissive() {
+        let permissive = Profile::Permissive;
+        let pdt = |input| permissive.parse_primitive_date_time(input).unwrap();
+        assert_eq!(pdt(" 2016-03-07t10:00 "), datetime!(2016-03-07 10:00));
+        assert_eq!(pdt("2016-03-07 10:00+0100"), datetime!(2016-03-07 09:00));
+        assert_eq!(pdt("2016-03-07 10:00-01"), datetime!(2016-03-07 11:00));
+        assert_eq!(pdt("2016-03-07 10:00+15:00"), datetime!(2016-03-06 19:00));
+        assert_eq!(
+            pdt("2016-03-07 10:00:00.123456789"),
+            datetime!(2016-03-07 10:00:00.123456789)
+        );
+        assert_eq!(pdt("2016-02-30 24:00"), datetime!(2016-03-02 00:00));
+        assert!(permissive
+            .parse_primitive_date_time("2016-03-07 10:00+24:00")
+            .is_err());
+    }
+
+    #[test]
+    fn test_profiled() {
+        use crate::matched::{OffsetStyle, Precision};
+        use crate::{First, Fourth};
+
+        // Accepted by both, and decoded as `fourth` does, not rounded to the millisecond
+        let sqlite = Fourth.with_profile(Profile::SqliteCompatible);
+        assert_eq!(
+            sqlite.parse_time("20:45:31.1339").unwrap(),
+            time!(20:45:31.1339)
+        );
+        // Each rejects text the other accepts, and explains it in its own terms
+        let error = sqlite.parse_time("10:0005").unwrap_err();
+        assert_eq!(error.attempts()[0].format(), "SQLite");
+        let error = sqlite.parse_time("24:00").unwrap_err();
+        assert_eq!(error.attempts()[0].format(), "TIME_DESCRIPTION");
+        assert!(Profile::SqliteCompatible.parse_time("24:00").is_ok());
+
+        let strict = First.with_profile(Profile::Strict);
+        assert!(strict.parse_date("2016-03-07").is_ok());
+        assert!(strict.parse_time("19:44").is_err());
+
+        let (time, format) = Fourth
+            .with_profile(Profile::Permissive)
+            .parse_time_with_format("20:45:31.133")
+            .unwrap();
+        assert_eq!(time, time!(20:45:31.133));
+        assert_eq!(
+            format,
+            MatchedFormat {
+                separator: None,
+                precision: Precision::Subseconds(3),
+                offset: OffsetStyle::None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_now() {
+        let before = OffsetDateTime::now_utc().replace_millisecond(0).unwrap();
+        let now = Profile::SqliteCompatible
+            .parse_offset_date_time("NOW")
+            .unwrap();
+        assert!(now >= before);
+        assert_eq!(now.nanosecond() % 1_000_000, 0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = Profile::SqliteCompatible
+            .parse_primitive_date_time("2013-09-17 23:59x")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "could not decode `2013-09-17 23:59x`: expected end of input after minute at byte 16 \
+             (SQLite)"
+        );
+
+        let error = Profile::SqliteCompatible
+            .parse_primitive_date_time("2016-13-01")
+            .unwrap_err();
+        assert_eq!(
+            error.furthest().unwrap().problem(),
+            &Problem::OutOfRange { component: "month" }
+        );
+        assert_eq!(error.furthest().unwrap().position(), 5);
+    }
+}
@@ -0,0 +1,180 @@
+//! Item-by-item parsing of format descriptions.
+//!
+//! `time` parses a whole description in one call and only reports which kind of item failed. The
+//! [`Walker`] drives [`Parsed::parse_item`] one item at a time instead, so callers can also learn
+//! how far the input matched and which optional items were present.
+
+use time::format_description::{Component, FormatItem};
+use time::parsing::Parsed;
+
+/// The components whose starting byte is remembered, in [`Walker::start_of`] order.
+const TRACKED: [&str; 9] = [
+    "year",
+    "month",
+    "day",
+    "hour",
+    "minute",
+    "second",
+    "subsecond",
+    "offset hour",
+    "offset minute",
+];
+
+/// The furthest point a walk failed to match at.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Stop<'a> {
+    pub(crate) position: usize,
+    /// The item that did not match.
+    pub(crate) expected: &'a FormatItem<'a>,
+    /// The last item that consumed input before it.
+    pub(crate) after: Option<&'a FormatItem<'a>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Walker<'a> {
+    input: &'a [u8],
+    pub(crate) parsed: Parsed,
+    /// Number of bytes of `input` consumed so far.
+    pub(crate) position: usize,
+    /// The last item that consumed input.
+    pub(crate) last: Option<&'a FormatItem<'a>>,
+    /// The furthest point any item, optional or not, failed to match.
+    pub(crate) stopped: Option<Stop<'a>>,
+    starts: [usize; TRACKED.len()],
+}
+
+impl<'a> Walker<'a> {
+    pub(crate) fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            parsed: Parsed::new(),
+            position: 0,
+            last: None,
+            stopped: None,
+            starts: [usize::MAX; TRACKED.len()],
+        }
+    }
+
+    pub(crate) fn remaining(&self) -> &'a [u8] {
+        &self.input[self.position..]
+    }
+
+    /// Whether the whole input has been consumed.
+    pub(crate) fn is_done(&self) -> bool {
+        self.position == self.input.len()
+    }
+
+    /// The byte where `component` started, if it was parsed.
+    pub(crate) fn start_of(&self, component: &str) -> Option<usize> {
+        let index = TRACKED.iter().position(|name| *name == component)?;
+        Some(self.starts[index]).filter(|start| *start != usize::MAX)
+    }
+
+    /// Parses each of `items` in turn, stopping at the first one that does not match.
+    pub(crate) fn items(&mut self, items: &'a [FormatItem<'a>]) -> bool {
+        items.iter().all(|item| self.item(item))
+    }
+
+    /// Parses a single item, returning whether it matched. A failed optional item leaves the
+    /// walker untouched apart from remembering where it stopped.
+    pub(crate) fn item(&mut self, item: &'a FormatItem<'a>) -> bool {
+        match item {
+            FormatItem::Optional(inner) => {
+                let snapshot = *self;
+                if !self.item(inner) {
+                    let stopped = self.stopped;
+                    *self = snapshot;
+                    self.stopped = stopped;
+                }
+                true
+            }
+            FormatItem::Compound(items) => self.items(items),
+            FormatItem::First(alternatives) => {
+                let snapshot = *self;
+                for alternative in alternatives.iter() {
+                    if self.item(alternative) {
+                        return true;
+                    }
+                    let stopped = self.stopped;
+                    *self = snapshot;
+                    self.stopped = stopped;
+                }
+                // Report the alternatives as a whole unless one of them got further
+                if self
+                    .stopped
+                    .is_none_or(|stop| stop.position <= self.position)
+                {
+                    self.stopped = None;
+                }
+                self.stop(item);
+                false
+            }
+            _ => match self.parsed.parse_item(self.remaining(), item) {
+                Ok(remaining) => {
+                    let start = self.position;
+                    self.position = self.input.len() - remaining.len();
+                    if let FormatItem::Component(component) = item {
+                        if let Some(index) =
+                            TRACKED.iter().position(|name| *name == name_of(component))
+                        {
+                            self.starts[index] = start;
+                        }
+                    }
+                    if self.position > start {
+                        self.last = Some(item);
+                    }
+                    true
+                }
+                Err(_) => {
+                    self.stop(item);
+                    false
+                }
+            },
+        }
+    }
+
+    /// Remembers the furthest point at which an item failed to match. The first item to fail at
+    /// a given position wins.
+    fn stop(&mut self, item: &'a FormatItem<'a>) {
+        if self
+            .stopped
+            .is_none_or(|stop| self.position > stop.position)
+        {
+            self.stopped = Some(Stop {
+                position: self.position,
+                expected: item,
+                after: self.last,
+            });
+        }
+    }
+}
+
+/// A short human-readable name for what `item` expects, such as `` `:` `` or `minute`.
+pub(crate) fn describe(item: &FormatItem<'_>) -> String {
+    match item {
+        FormatItem::Literal(literal) => format!("`{}`", String::from_utf8_lossy(literal)),
+        FormatItem::Component(component) => name_of(component).to_string(),
+        FormatItem::Optional(inner) => describe(inner),
+        FormatItem::Compound(items) => items.first().map_or_else(String::new, describe),
+        FormatItem::First(alternatives) => {
+            let names: Vec<_> = alternatives.iter().map(describe).collect();
+            format!("one of {}", names.join(", "))
+        }
+        _ => "item".to_string(),
+    }
+}
+
+fn name_of(component: &Component) -> &'static str {
+    match component {
+        Component::Year(_) => "year",
+        Component::Month(_) => "month",
+        Component::Day(_) => "day",
+        Component::Hour(_) => "hour",
+        Component::Minute(_) => "minute",
+        Component::Second(_) => "second",
+        Component::Subsecond(_) => "subsecond",
+        Component::OffsetHour(_) => "offset hour",
+        Component::OffsetMinute(_) => "offset minute",
+        _ => "component",
+    }
+}
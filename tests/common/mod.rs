@@ -0,0 +1,258 @@
+//! Reading the corpus files under `tests/corpus`.
+//!
+//! Each integration test that uses this module only uses part of it.
+#![allow(dead_code)]
+
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+use time_tests::matched::{MatchedFormat, OffsetStyle, Precision, Separator};
+use time_tests::{First, FormatReportingParser, Fourth, Problem, Second, Third};
+
+/// Every strategy the golden corpus is run against. A new iteration is covered by adding it here.
+pub const STRATEGIES: [(&str, &dyn FormatReportingParser); 4] = [
+    ("first", &First),
+    ("second", &Second),
+    ("third", &Third),
+    ("fourth", &Fourth),
+];
+
+/// The version of the golden corpus format this module reads.
+const GOLDEN_VERSION: &str = "1";
+
+/// The tab separated fields of each line that is not blank or a `#` comment, with its line
+/// number.
+pub fn rows(corpus: &str) -> impl Iterator<Item = (usize, Vec<&str>)> {
+    corpus
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| (index + 1, line.split('\t').collect()))
+}
+
+/// One line of the golden corpus.
+#[derive(Debug)]
+pub struct Case<'a> {
+    pub line: usize,
+    pub input: &'a str,
+    pub expected: Expected,
+    /// The format `fourth` reports, if the input decodes.
+    pub format: Option<MatchedFormat>,
+    /// The strategies the case applies to, or `None` for all of them.
+    pub strategies: Option<Vec<&'a str>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expected {
+    OffsetDateTime(OffsetDateTime),
+    PrimitiveDateTime(PrimitiveDateTime),
+    Time(Time),
+    Date(Date),
+    /// Rejected, with the problem of the furthest attempt written as in the corpus.
+    Error {
+        kind: Kind,
+        problems: Problems,
+    },
+}
+
+/// The problem a rejected input is expected to have, which can differ between strategies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problems {
+    default: String,
+    overrides: Vec<(String, String)>,
+}
+
+impl Problems {
+    pub fn of(&self, strategy: &str) -> &str {
+        self.overrides
+            .iter()
+            .find(|(name, _)| name == strategy)
+            .map_or(&self.default, |(_, problem)| problem)
+    }
+}
+
+/// The type a case decodes to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    OffsetDateTime,
+    PrimitiveDateTime,
+    Time,
+    Date,
+}
+
+impl Case<'_> {
+    pub fn kind(&self) -> Kind {
+        match &self.expected {
+            Expected::OffsetDateTime(_) => Kind::OffsetDateTime,
+            Expected::PrimitiveDateTime(_) => Kind::PrimitiveDateTime,
+            Expected::Time(_) => Kind::Time,
+            Expected::Date(_) => Kind::Date,
+            Expected::Error { kind, .. } => *kind,
+        }
+    }
+
+    pub fn applies_to(&self, strategy: &str) -> bool {
+        self.strategies
+            .as_ref()
+            .is_none_or(|strategies| strategies.contains(&strategy))
+    }
+}
+
+const OFFSET_DATE_TIME: &[FormatItem<'_>] = format_description!(
+    "[year]-[month]-[day] [hour]:[minute]:[second][optional [.[subsecond]]] [offset_hour sign:mandatory]:[offset_minute]"
+);
+const PRIMITIVE_DATE_TIME: &[FormatItem<'_>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second][optional [.[subsecond]]]");
+const TIME: &[FormatItem<'_>] =
+    format_description!("[hour]:[minute]:[second][optional [.[subsecond]]]");
+const DATE: &[FormatItem<'_>] = format_description!("[year]-[month]-[day]");
+
+/// Reads the golden corpus, panicking with the line number on anything malformed.
+///
+/// The first row must be `version` and the format version. Every other row has six fields:
+///
+/// - the type: `offset`, `primitive`, `time` or `date`
+/// - the input
+/// - the expected value, written `YYYY-MM-DD HH:MM:SS[.F] +HH:MM` with the parts the type has,
+///   or `-` if the input is rejected
+/// - the format `fourth` reports, as separator, precision and offset: `space`, `T`, `none`,
+///   `space+T` or `-`; `minutes`, `seconds` or `subseconds:N`; and `none`, `Z`, `+HH:MM`,
+///   `+HHMM`, `+HH:` or `+HH`. `-` for dates and rejected inputs.
+/// - the problem with the furthest attempt for a rejected input, `expected`, `trailing`,
+///   `range:COMPONENT` or `rejected`, or `-`. It can be followed by `STRATEGY=PROBLEM` entries,
+///   separated by spaces, for strategies that report something else.
+/// - the strategies the case applies to, separated by commas, or `*` for all of them
+pub fn golden(corpus: &str) -> Vec<Case<'_>> {
+    let mut rows = rows(corpus);
+    match rows.next() {
+        Some((_, fields)) if fields == ["version", GOLDEN_VERSION] => {}
+        other => panic!("expected `version\\t{GOLDEN_VERSION}` first, found {other:?}"),
+    }
+
+    rows.map(|(line, fields)| {
+        let [kind, input, value, format, problem, strategies] = fields[..] else {
+            panic!("line {line}: expected 6 fields, found {}", fields.len());
+        };
+        let kind = match kind {
+            "offset" => Kind::OffsetDateTime,
+            "primitive" => Kind::PrimitiveDateTime,
+            "time" => Kind::Time,
+            "date" => Kind::Date,
+            other => panic!("line {line}: unknown type `{other}`"),
+        };
+        let expected = match (value, problem) {
+            ("-", "-") => panic!("line {line}: neither a value nor a problem"),
+            ("-", problems) => Expected::Error {
+                kind,
+                problems: parse_problems(problems)
+                    .unwrap_or_else(|| panic!("line {line}: cannot read problem `{problems}`")),
+            },
+            (value, "-") => parse_value(kind, value)
+                .unwrap_or_else(|| panic!("line {line}: cannot read value `{value}`")),
+            _ => panic!("line {line}: both a value and a problem"),
+        };
+        let format = match format {
+            "-" => None,
+            format => Some(
+                parse_format(format)
+                    .unwrap_or_else(|| panic!("line {line}: cannot read format `{format}`")),
+            ),
+        };
+        let strategies = match strategies {
+            "*" => None,
+            names => Some(names.split(',').collect()),
+        };
+
+        Case {
+            line,
+            input,
+            expected,
+            format,
+            strategies,
+        }
+    })
+    .collect()
+}
+
+fn parse_value(kind: Kind, value: &str) -> Option<Expected> {
+    Some(match kind {
+        Kind::OffsetDateTime => {
+            Expected::OffsetDateTime(OffsetDateTime::parse(value, OFFSET_DATE_TIME).ok()?)
+        }
+        Kind::PrimitiveDateTime => {
+            Expected::PrimitiveDateTime(PrimitiveDateTime::parse(value, PRIMITIVE_DATE_TIME).ok()?)
+        }
+        Kind::Time => Expected::Time(Time::parse(value, TIME).ok()?),
+        Kind::Date => Expected::Date(Date::parse(value, DATE).ok()?),
+    })
+}
+
+fn parse_problems(problems: &str) -> Option<Problems> {
+    let mut entries = problems.split(' ');
+    let default = entries.next()?.to_owned();
+    let overrides = entries
+        .map(|entry| {
+            let (strategy, problem) = entry.split_once('=')?;
+            Some((strategy.to_owned(), problem.to_owned()))
+        })
+        .collect::<Option<_>>()?;
+
+    Some(Problems { default, overrides })
+}
+
+fn parse_format(format: &str) -> Option<MatchedFormat> {
+    let mut parts = format.split(' ');
+    let separator = match parts.next()? {
+        "space" => Some(Separator::Space),
+        "T" => Some(Separator::T),
+        "none" => Some(Separator::None),
+        "space+T" => Some(Separator::SpaceAndT),
+        "-" => None,
+        _ => return None,
+    };
+    let precision = match parts.next()? {
+        "minutes" => Precision::Minutes,
+        "seconds" => Precision::Seconds,
+        subseconds => Precision::Subseconds(subseconds.strip_prefix("subseconds:")?.parse().ok()?),
+    };
+    let offset = match parts.next()? {
+        "none" => OffsetStyle::None,
+        "Z" => OffsetStyle::Zulu,
+        "+HH:MM" => OffsetStyle::Numeric {
+            colon: true,
+            minutes: true,
+        },
+        "+HHMM" => OffsetStyle::Numeric {
+            colon: false,
+            minutes: true,
+        },
+        "+HH:" => OffsetStyle::Numeric {
+            colon: true,
+            minutes: false,
+        },
+        "+HH" => OffsetStyle::Numeric {
+            colon: false,
+            minutes: false,
+        },
+        _ => return None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some(MatchedFormat {
+        separator,
+        precision,
+        offset,
+    })
+}
+
+/// The problem as the golden corpus writes it.
+pub fn problem_kind(problem: &Problem) -> String {
+    match problem {
+        Problem::Expected { .. } => "expected".to_owned(),
+        Problem::TrailingCharacters { .. } => "trailing".to_owned(),
+        Problem::OutOfRange { component } => format!("range:{component}"),
+        Problem::Rejected => "rejected".to_owned(),
+    }
+}
@@ -0,0 +1,53 @@
+//! Checks that the `formats` building blocks are what the equivalent `format_description!`
+//! produces, so the baseline benchmark compares descriptions built from them with the macro like
+//! for like.
+
+use time::format_description::FormatItem;
+use time::macros::format_description as fd;
+use time_tests::formats::*;
+
+/// The only item of a description.
+fn item<'a>(description: &'a [FormatItem<'a>]) -> &'a FormatItem<'a> {
+    let [item] = description else {
+        panic!("expected one item, found {description:?}");
+    };
+    item
+}
+
+#[test]
+fn test_components_match_the_macro() {
+    assert_eq!(&YEAR, item(fd!("[year]")));
+    assert_eq!(&MONTH, item(fd!("[month]")));
+    assert_eq!(&DAY, item(fd!("[day]")));
+    assert_eq!(&HOUR, item(fd!("[hour]")));
+    assert_eq!(&MINUTE, item(fd!("[minute]")));
+    assert_eq!(&SECOND, item(fd!("[second]")));
+    assert_eq!(&SUBSECOND, item(fd!("[subsecond]")));
+    assert_eq!(&OFFSET_HOUR, item(fd!("[offset_hour sign:mandatory]")));
+    assert_eq!(&OFFSET_MINUTE, item(fd!("[offset_minute]")));
+}
+
+#[test]
+fn test_descriptions_match_the_macro() {
+    assert_eq!(DATE, fd!("[year]-[month]-[day]"));
+    assert_eq!(
+        SORTABLE_UTC,
+        fd!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:9]+00:00")
+    );
+
+    #[cfg(all(
+        not(feature = "default-format-iso8601-t"),
+        not(feature = "default-format-millis")
+    ))]
+    {
+        assert_eq!(
+            DEFAULT_OFFSET_DATE_TIME,
+            fd!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond][offset_hour sign:mandatory]:[offset_minute]")
+        );
+        assert_eq!(
+            DEFAULT_PRIMITIVE_DATE_TIME,
+            fd!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond]")
+        );
+        assert_eq!(DEFAULT_TIME, fd!("[hour]:[minute]:[second].[subsecond]"));
+    }
+}
@@ -0,0 +1,123 @@
+//! Runs every strategy against the golden corpus in `corpus/golden.tsv`.
+
+mod common;
+
+use common::{Case, Expected, Kind, STRATEGIES};
+use time_tests::matched::MatchedFormat;
+use time_tests::{Error, FormatReportingParser, SqliteTemporalParser};
+
+const CORPUS: &str = include_str!("corpus/golden.tsv");
+
+/// What `parser` makes of the case's input, in the form the corpus expects.
+fn decode(parser: &dyn SqliteTemporalParser, case: &Case<'_>) -> Result<Expected, Error> {
+    let input = case.input;
+    Ok(match case.kind() {
+        Kind::OffsetDateTime => Expected::OffsetDateTime(parser.parse_offset_date_time(input)?),
+        Kind::PrimitiveDateTime => {
+            Expected::PrimitiveDateTime(parser.parse_primitive_date_time(input)?)
+        }
+        Kind::Time => Expected::Time(parser.parse_time(input)?),
+        Kind::Date => Expected::Date(parser.parse_date(input)?),
+    })
+}
+
+/// Returns why `parser` does not decode the case as the corpus says, if it does not.
+fn check(name: &str, parser: &dyn SqliteTemporalParser, case: &Case<'_>) -> Option<String> {
+    match (&case.expected, decode(parser, case)) {
+        (Expected::Error { problems, .. }, Err(error)) => {
+            let problem = error
+                .furthest()
+                .map_or_else(|| "none".to_owned(), |a| common::problem_kind(a.problem()));
+            (problem != problems.of(name)).then(|| format!("rejected with `{problem}`: {error}"))
+        }
+        (Expected::Error { .. }, Ok(value)) => Some(format!("accepted as {value:?}")),
+        (expected, Ok(value)) => {
+            // `OffsetDateTime` equality compares instants, so compare the offsets as well
+            let same_offset = match (expected, &value) {
+                (Expected::OffsetDateTime(expected), Expected::OffsetDateTime(value)) => {
+                    expected.offset() == value.offset()
+                }
+                _ => true,
+            };
+            (*expected != value || !same_offset).then(|| format!("decoded as {value:?}"))
+        }
+        (_, Err(error)) => Some(format!("rejected: {error}")),
+    }
+}
+
+/// The format `parser` reports for the case's input, if it decodes it. The value decoded along
+/// with the format must be the one decoded without it.
+fn matched_format(parser: &dyn FormatReportingParser, case: &Case<'_>) -> Option<MatchedFormat> {
+    let input = case.input;
+    let (value, format) = match case.kind() {
+        Kind::OffsetDateTime => parser
+            .parse_offset_date_time_with_format(input)
+            .map(|(dt, format)| (Expected::OffsetDateTime(dt), format)),
+        Kind::PrimitiveDateTime => parser
+            .parse_primitive_date_time_with_format(input)
+            .map(|(dt, format)| (Expected::PrimitiveDateTime(dt), format)),
+        Kind::Time => parser
+            .parse_time_with_format(input)
+            .map(|(time, format)| (Expected::Time(time), format)),
+        Kind::Date => return None,
+    }
+    .ok()?;
+    assert_eq!(Some(value), decode(parser, case).ok(), "{input:?}");
+    Some(format)
+}
+
+#[test]
+fn test_golden_corpus() {
+    let cases = common::golden(CORPUS);
+    assert!(!cases.is_empty());
+
+    let mut failures = Vec::new();
+    for case in &cases {
+        for (name, parser) in STRATEGIES {
+            if !case.applies_to(name) {
+                continue;
+            }
+            if let Some(failure) = check(name, parser, case) {
+                failures.push(format!(
+                    "line {} {name} {:?}: {failure}",
+                    case.line, case.input
+                ));
+            }
+            let format = matched_format(parser, case);
+            if case.format != format {
+                failures.push(format!(
+                    "line {} {name} {:?}: reported {format:?}",
+                    case.line, case.input
+                ));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} cases failed:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_every_strategy_is_covered() {
+    // Each strategy should be tested on every kind of value, not only on rows naming it
+    let cases = common::golden(CORPUS);
+    for (name, _) in STRATEGIES {
+        for kind in [
+            Kind::OffsetDateTime,
+            Kind::PrimitiveDateTime,
+            Kind::Time,
+            Kind::Date,
+        ] {
+            assert!(
+                cases
+                    .iter()
+                    .any(|case| case.kind() == kind && case.applies_to(name)),
+                "no {kind:?} cases for {name}"
+            );
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Runs every iteration against `corpus/negative.tsv`, which records which out of range text each
+//! one accepts and how `time`'s range checks surface through `third` and `fourth`.
+
+mod common;
+
+use time::error::{Parse, ParseFromDescription, TryFromParsed};
+use time_tests::{fourth, third};
+use time_tests::{First, Fourth, Second, SqliteTemporalParser, Third};
+
+const CORPUS: &str = include_str!("corpus/negative.tsv");
+
+/// The `time` error as the corpus writes it.
+fn error_kind(error: Parse) -> String {
+    match error {
+        Parse::TryFromParsed(TryFromParsed::ComponentRange(range)) => {
+            format!("range:{}", range.name())
+        }
+        Parse::TryFromParsed(TryFromParsed::InsufficientInformation) => "insufficient".to_owned(),
+        Parse::ParseFromDescription(ParseFromDescription::InvalidComponent(name)) => {
+            format!("component:{name}")
+        }
+        Parse::ParseFromDescription(ParseFromDescription::InvalidLiteral { .. }) => {
+            "literal".to_owned()
+        }
+        Parse::ParseFromDescription(ParseFromDescription::UnexpectedTrailingCharacters {
+            ..
+        }) => "trailing".to_owned(),
+        error => format!("unknown:{error}"),
+    }
+}
+
+fn outcome<T, E>(result: Result<T, E>) -> &'static str {
+    if result.is_ok() {
+        "accept"
+    } else {
+        "reject"
+    }
+}
+
+fn kind<T>(result: Result<T, Parse>) -> String {
+    result.map_or_else(error_kind, |_| "-".to_owned())
+}
+
+/// What each iteration and the `third` and `fourth` modules do with `input`, as a corpus row.
+fn actual(kind_of_value: &str, input: &str) -> Vec<String> {
+    let parsers: [&dyn SqliteTemporalParser; 4] = [&First, &Second, &Third, &Fourth];
+    let (outcomes, third_error, fourth_error): (Vec<_>, _, _) = match kind_of_value {
+        "date" => (
+            parsers.map(|p| outcome(p.parse_date(input))).into(),
+            kind(third::date_iteration(input)),
+            kind(fourth::date_iteration(input)),
+        ),
+        "time" => (
+            parsers.map(|p| outcome(p.parse_time(input))).into(),
+            kind(third::time_iteration(input)),
+            kind(fourth::time_iteration(input)),
+        ),
+        "primitive" => (
+            parsers
+                .map(|p| outcome(p.parse_primitive_date_time(input)))
+                .into(),
+            kind(third::pdt_iteration(input)),
+            kind(fourth::pdt_iteration(input)),
+        ),
+        "offset" => (
+            parsers
+                .map(|p| outcome(p.parse_offset_date_time(input)))
+                .into(),
+            kind(third::odt_iteration(input)),
+            kind(fourth::odt_iteration(input)),
+        ),
+        other => panic!("unknown type `{other}`"),
+    };
+
+    let mut row = vec![kind_of_value.to_owned(), input.to_owned()];
+    row.extend(outcomes.into_iter().map(str::to_owned));
+    row.push(third_error);
+    row.push(fourth_error);
+    row
+}
+
+#[test]
+fn test_negative_corpus() {
+    let mut rows = 0;
+    let mut mismatches = Vec::new();
+    for (line, expected) in common::rows(CORPUS) {
+        assert_eq!(
+            expected.len(),
+            8,
+            "line {line} has the wrong number of columns"
+        );
+
+        let actual = actual(expected[0], expected[1]);
+        if actual != expected {
+            mismatches.push(format!(
+                "line {line}\n    corpus: {}\n    actual: {}",
+                expected.join(" "),
+                actual.join(" ")
+            ));
+        }
+        rows += 1;
+    }
+
+    assert!(rows > 0);
+    assert!(
+        mismatches.is_empty(),
+        "{} rows no longer match:\n{}",
+        mismatches.len(),
+        mismatches.join("\n")
+    );
+}
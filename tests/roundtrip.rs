@@ -0,0 +1,226 @@
+//! Property tests that render generated values in each of SQLite's time value formats and check
+//! that every decoder recovers them, less the digits the format does not keep.
+//!
+//! SQLite numbers its formats 1 to 12. Formats 2 to 10 are generated with each offset style the
+//! decoded type allows, format 1 is a date, and format 12 is a Julian day number, which only the
+//! profiles read. Format 11, `now`, has no fixed value to compare against.
+
+use proptest::prelude::*;
+use time::{Date, PrimitiveDateTime, Time, UtcOffset};
+use time_tests::chain::FallbackChain;
+use time_tests::config::ParserConfig;
+use time_tests::encode::pdt_julian_day;
+use time_tests::fourth;
+use time_tests::matched::{MatchedFormat, OffsetStyle, Precision, Separator};
+use time_tests::profile::Profile;
+use time_tests::{First, Fourth, Second, SqliteTemporalParser, Third};
+
+fn date() -> impl Strategy<Value = Date> {
+    let first = Date::from_calendar_date(0, time::Month::January, 1).unwrap();
+    let last = Date::from_calendar_date(9999, time::Month::December, 31).unwrap();
+    (first.to_julian_day()..=last.to_julian_day())
+        .prop_map(|day| Date::from_julian_day(day).unwrap())
+}
+
+fn time() -> impl Strategy<Value = Time> {
+    (0u8..24, 0u8..60, 0u8..60, 0u32..1_000_000_000).prop_map(|(hour, minute, second, nano)| {
+        Time::from_hms_nano(hour, minute, second, nano).unwrap()
+    })
+}
+
+/// Every offset the `time` descriptions and `Rfc3339` can both read.
+fn offset() -> impl Strategy<Value = UtcOffset> {
+    (-(23 * 60 + 59)..=23 * 60 + 59)
+        .prop_map(|minutes: i32| UtcOffset::from_whole_seconds(minutes * 60).unwrap())
+}
+
+fn separator() -> impl Strategy<Value = Separator> {
+    prop_oneof![Just(Separator::Space), Just(Separator::T)]
+}
+
+fn precision() -> impl Strategy<Value = Precision> {
+    prop_oneof![
+        Just(Precision::Minutes),
+        Just(Precision::Seconds),
+        (1u8..=9).prop_map(Precision::Subseconds),
+    ]
+}
+
+fn render_date(date: Date) -> String {
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.year(),
+        date.month() as u8,
+        date.day()
+    )
+}
+
+fn render_separator(separator: Separator) -> &'static str {
+    match separator {
+        Separator::Space => " ",
+        Separator::T => "T",
+        Separator::None => "",
+        Separator::SpaceAndT => " T",
+    }
+}
+
+fn render_time(time: Time, precision: Precision) -> String {
+    let mut text = format!("{:02}:{:02}", time.hour(), time.minute());
+    if precision != Precision::Minutes {
+        text.push_str(&format!(":{:02}", time.second()));
+    }
+    if let Precision::Subseconds(digits) = precision {
+        let nanosecond = format!("{:09}", time.nanosecond());
+        text.push('.');
+        text.push_str(&nanosecond[..digits as usize]);
+    }
+    text
+}
+
+fn render_offset(offset: UtcOffset) -> String {
+    let (hours, minutes, _) = offset.as_hms();
+    let sign = if offset.is_negative() { '-' } else { '+' };
+    format!("{sign}{:02}:{:02}", hours.abs(), minutes.abs())
+}
+
+/// `time` without the digits `precision` leaves out.
+fn truncate(time: Time, precision: Precision) -> Time {
+    let nanosecond = match precision {
+        Precision::Minutes | Precision::Seconds => 0,
+        Precision::Subseconds(digits) => {
+            let scale = 10u32.pow(9 - digits as u32);
+            time.nanosecond() / scale * scale
+        }
+    };
+    let second = if precision == Precision::Minutes {
+        0
+    } else {
+        time.second()
+    };
+    Time::from_hms_nano(time.hour(), time.minute(), second, nanosecond).unwrap()
+}
+
+/// The decoders that should read text in `precision` with `offset` exactly.
+fn decoders(
+    precision: Precision,
+    offset: UtcOffset,
+) -> Vec<(&'static str, Box<dyn SqliteTemporalParser>)> {
+    let mut decoders: Vec<(&'static str, Box<dyn SqliteTemporalParser>)> = vec![
+        ("first", Box::new(First)),
+        ("second", Box::new(Second)),
+        ("third", Box::new(Third)),
+        ("fourth", Box::new(Fourth)),
+        ("configured", Box::new(ParserConfig::new().build())),
+        ("chain", Box::new(FallbackChain::new(Profile::Permissive))),
+        ("permissive", Box::new(Profile::Permissive)),
+    ];
+    // SQLite keeps milliseconds, rounding the rest, and allows offsets of up to 14 hours
+    let keeps_precision = !matches!(precision, Precision::Subseconds(4..));
+    if keeps_precision && offset.whole_hours().abs() <= 14 {
+        decoders.push(("SQLite", Box::new(Profile::SqliteCompatible)));
+    }
+    decoders
+}
+
+proptest! {
+    #[test]
+    fn test_offset_date_times(
+        date in date(),
+        time in time(),
+        offset in offset(),
+        separator in separator(),
+        precision in precision(),
+    ) {
+        let text = format!(
+            "{}{}{}{}",
+            render_date(date),
+            render_separator(separator),
+            render_time(time, precision),
+            render_offset(offset)
+        );
+        let expected = PrimitiveDateTime::new(date, truncate(time, precision)).assume_offset(offset);
+
+        for (name, decoder) in decoders(precision, offset) {
+            // `OffsetDateTime` equality compares instants, so compare the offsets as well
+            let decoded = decoder.parse_offset_date_time(&text).ok().map(|dt| (dt, dt.offset()));
+            prop_assert_eq!(decoded, Some((expected, offset)), "{} decoding {:?}", name, text);
+        }
+        let (_, matched) = fourth::odt_iteration_with_format(&text).unwrap();
+        prop_assert_eq!(
+            matched,
+            MatchedFormat {
+                separator: Some(separator),
+                precision,
+                offset: OffsetStyle::Numeric { colon: true, minutes: true },
+            }
+        );
+    }
+
+    #[test]
+    fn test_primitive_date_times(
+        date in date(),
+        time in time(),
+        separator in separator(),
+        precision in precision(),
+        zulu in any::<bool>(),
+    ) {
+        let text = format!(
+            "{}{}{}{}",
+            render_date(date),
+            render_separator(separator),
+            render_time(time, precision),
+            if zulu { "Z" } else { "" }
+        );
+        let expected = PrimitiveDateTime::new(date, truncate(time, precision));
+
+        for (name, decoder) in decoders(precision, UtcOffset::UTC) {
+            let decoded = decoder.parse_primitive_date_time(&text).ok();
+            prop_assert_eq!(decoded, Some(expected), "{} decoding {:?}", name, text);
+        }
+        let (_, matched) = fourth::pdt_iteration_with_format(&text).unwrap();
+        prop_assert_eq!(
+            matched,
+            MatchedFormat {
+                separator: Some(separator),
+                precision,
+                offset: if zulu { OffsetStyle::Zulu } else { OffsetStyle::None },
+            }
+        );
+    }
+
+    #[test]
+    fn test_times(time in time(), precision in precision()) {
+        let text = render_time(time, precision);
+        let expected = truncate(time, precision);
+
+        for (name, decoder) in decoders(precision, UtcOffset::UTC) {
+            let decoded = decoder.parse_time(&text).ok();
+            prop_assert_eq!(decoded, Some(expected), "{} decoding {:?}", name, text);
+        }
+        let (_, matched) = fourth::time_iteration_with_format(&text).unwrap();
+        prop_assert_eq!(
+            matched,
+            MatchedFormat { separator: None, precision, offset: OffsetStyle::None }
+        );
+    }
+
+    #[test]
+    fn test_dates(date in date()) {
+        let text = render_date(date);
+
+        for (name, decoder) in decoders(Precision::Minutes, UtcOffset::UTC) {
+            prop_assert_eq!(decoder.parse_date(&text).ok(), Some(date), "{} decoding {:?}", name, text);
+        }
+    }
+
+    #[test]
+    fn test_julian_days(date in date(), time in time()) {
+        let expected = PrimitiveDateTime::new(date, truncate(time, Precision::Subseconds(3)));
+        let text = pdt_julian_day(expected).to_string();
+
+        for profile in [Profile::SqliteCompatible, Profile::Permissive] {
+            let decoded = profile.parse_primitive_date_time(&text).ok();
+            prop_assert_eq!(decoded, Some(expected), "{:?} decoding {:?}", profile, text);
+        }
+    }
+}
@@ -0,0 +1,381 @@
+//! Runs edge-case and random text through SQLite's own `datetime()`, `time()` and `julianday()`
+//! and checks that `Profile::SqliteCompatible` agrees with every result, including which inputs
+//! are rejected. The iteration strategies are checked against `julianday()` as well, and the
+//! numeric encoders against `julianday()` and `unixepoch()`.
+
+mod common;
+
+use common::STRATEGIES;
+use proptest::prelude::RngExt;
+use proptest::test_runner::{RngAlgorithm, TestRng};
+use rusqlite::Connection;
+use time::format_description::FormatItem;
+use time::macros::date;
+use time::macros::format_description;
+use time::{Duration, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+use time_tests::encode::{odt_julian_day, odt_unix_epoch, pdt_julian_day, pdt_unix_epoch};
+use time_tests::profile::Profile;
+use time_tests::{First, Fourth, Second, SqliteTemporalParser, Third};
+
+const DATE_TIME: &[FormatItem<'_>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+const TIME: &[FormatItem<'_>] = format_description!("[hour]:[minute]:[second]");
+const NANOSECONDS: &[FormatItem<'_>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:9]");
+const OFFSET: &[FormatItem<'_>] =
+    format_description!("[offset_hour sign:mandatory]:[offset_minute]");
+
+/// Inputs picked for the rules in the `SqliteCompatible` grammar.
+const EDGE_CASES: &[&str] = &[
+    "2016-03-07",
+    "2016-03-07 22:36",
+    "2016-03-07 22:36:55",
+    "2016-03-07 22:36:55.135",
+    "2016-03-07T22:36:55.135",
+    "2016-03-07t22:36:55.135",
+    "2016-03-0722:36",
+    "2016-03-07 T22:36",
+    "2016-03-07T 22:36",
+    "2016-03-07  22:36",
+    "2016-03-07\t22:36",
+    " 2016-03-07 22:36",
+    "2016-03-07 22:36 ",
+    "2016-03-07 22:36Z",
+    "2016-03-07 22:36z",
+    "2016-03-07 22:36 Z ",
+    "2016-03-07 22:36+03:30",
+    "2016-03-07 22:36 -14:00",
+    "2016-03-07 22:36+15:00",
+    "2016-03-07 22:36+0330",
+    "2016-03-07 22:36+03",
+    "2016-03-07 22:36:55.",
+    "2016-03-07 22:36:55.9999",
+    "2016-03-07 22:36:55.0005",
+    "2016-03-07 22:36:55.123456789012",
+    "2016-03-07 22:60",
+    "2016-03-07 22:36:60",
+    "2016-03-07 25:00",
+    "2016-02-29 12:00",
+    "2015-02-29 12:00",
+    "2016-02-30 12:00",
+    "2016-02-31",
+    "2016-04-31 23:59:59.999",
+    "2016-03-32",
+    "2016-13-01",
+    "2016-00-01",
+    "2016-01-00",
+    "2016-03-29 24:00",
+    "2016-03-29 24:00:00.000",
+    "2016-03-29 24:00+01:00",
+    "2016-12-31 24:00",
+    "24:00",
+    "22:36",
+    "22:36:55.135",
+    "22:36:55.135+05:00",
+    "00:00-01:00",
+    "0000-01-01 00:00",
+    "-0001-12-31 23:59",
+    "-4713-11-24 12:00",
+    "-4713-11-24 11:59:59.999",
+    "-4714-12-31",
+    "9999-12-31 23:59:59.999",
+    "9999-12-31 23:59:59.9999",
+    "9999-12-31 23:59+01:00",
+    "9999-12-31 23:59-01:00",
+    "16-03-07",
+    "2016-3-7",
+    "2016/03/07",
+    "2457455.442304803",
+    "2457455",
+    " 2457455.5 ",
+    "0",
+    "0.0",
+    "-1",
+    "5373484.499999",
+    "5373484.5",
+    "1e6",
+    "",
+    " ",
+    "T",
+    "Z",
+    "+05:00",
+];
+
+/// A source of random inputs that are the same on every run.
+struct Inputs(TestRng);
+
+impl Inputs {
+    fn below(&mut self, bound: u64) -> u64 {
+        self.0.random_range(0..bound)
+    }
+
+    fn pick<'a>(&mut self, choices: &[&'a str]) -> &'a str {
+        choices[self.below(choices.len() as u64) as usize]
+    }
+
+    /// Text close to SQLite's layouts, with every field drawn from a range a little wider than
+    /// the valid one, and sometimes a character replaced.
+    fn input(&mut self) -> String {
+        if self.below(10) == 0 {
+            let day = self.0.random::<f64>() * 5_400_000.0 - 10_000.0;
+            return format!("{day}");
+        }
+
+        let mut text = String::new();
+        let with_date = self.below(8) != 0;
+        if with_date {
+            let year = match self.below(6) {
+                0 => self.below(10_000) as i64 - 4714,
+                1 => 9999,
+                _ => 1900 + self.below(200) as i64,
+            };
+            if year < 0 {
+                text.push_str(&format!("-{:04}", -year));
+            } else {
+                text.push_str(&format!("{year:04}"));
+            }
+            text.push_str(&format!("-{:02}-{:02}", self.below(14), self.below(33)));
+        }
+        if !with_date || self.below(5) != 0 {
+            if with_date {
+                text.push_str(self.pick(&[" ", " ", "T", "T", "t", "", " T", "  ", "\t"]));
+            }
+            let hour = if self.below(10) == 0 {
+                24
+            } else {
+                self.below(26)
+            };
+            text.push_str(&format!("{hour:02}:{:02}", self.below(61)));
+            if self.below(3) != 0 {
+                text.push_str(&format!(":{:02}", self.below(61)));
+                if self.below(2) == 0 {
+                    text.push('.');
+                    for _ in 0..self.below(13) {
+                        text.push(char::from(b'0' + self.below(10) as u8));
+                    }
+                }
+            }
+            text.push_str(self.pick(&[
+                "", "", "", "Z", "z", " Z", "Z ", "+01:00", "-05:30", " +14:00", "-14:00 ",
+                "+15:00", "+0100", "+01", "+1:00", "-23:59",
+            ]));
+        }
+
+        if self.below(10) == 0 && !text.is_empty() {
+            let position = self.below(text.len() as u64) as usize;
+            let replacement = self.pick(&["", " ", "0", "9", ":", "-", "+", ".", "T", "x"]);
+            text.replace_range(position..=position, replacement);
+        }
+        text
+    }
+}
+
+fn random_inputs() -> Vec<String> {
+    let mut inputs = Inputs(TestRng::deterministic_rng(RngAlgorithm::ChaCha));
+    (0..20_000).map(|_| inputs.input()).collect()
+}
+
+/// What SQLite returns for `datetime(x)`, `time(x)` and `julianday(x)`.
+#[derive(Debug, PartialEq)]
+struct Results {
+    date_time: Option<String>,
+    time: Option<String>,
+    julian_day: Option<f64>,
+}
+
+fn sqlite(connection: &Connection, input: &str) -> Results {
+    connection
+        .query_row(
+            "SELECT datetime(?1), time(?1), julianday(?1)",
+            [input],
+            |row| {
+                Ok(Results {
+                    date_time: row.get(0)?,
+                    time: row.get(1)?,
+                    julian_day: row.get(2)?,
+                })
+            },
+        )
+        .unwrap()
+}
+
+fn decoded(input: &str) -> Results {
+    let profile = Profile::SqliteCompatible;
+    let date_time = profile.parse_primitive_date_time(input).ok();
+    Results {
+        date_time: date_time.map(|dt| dt.format(DATE_TIME).unwrap()),
+        time: profile
+            .parse_time(input)
+            .ok()
+            .map(|time| time.format(TIME).unwrap()),
+        julian_day: date_time.map(pdt_julian_day),
+    }
+}
+
+#[test]
+fn test_agrees_with_sqlite() {
+    let connection = Connection::open_in_memory().unwrap();
+    let random = random_inputs();
+
+    let mut disagreements = Vec::new();
+    for input in EDGE_CASES
+        .iter()
+        .copied()
+        .chain(random.iter().map(String::as_str))
+    {
+        let expected = sqlite(&connection, input);
+        let actual = decoded(input);
+        // SQLite writes a time of hour 24 as it was given, `24:MM:SS`, without deciding which day
+        // it is on, so only the Julian day says which instant it found.
+        let hour_24 = expected
+            .time
+            .as_deref()
+            .is_some_and(|time| time.starts_with("24:"));
+        let agrees = if hour_24 {
+            actual.julian_day == expected.julian_day
+        } else {
+            actual == expected
+        };
+        if !agrees {
+            disagreements.push(format!(
+                "{input:?}\n    SQLite:  {expected:?}\n    decoded: {actual:?}"
+            ));
+        }
+    }
+
+    assert!(
+        disagreements.is_empty(),
+        "{} inputs decoded differently from SQLite:\n{}",
+        disagreements.len(),
+        disagreements.join("\n")
+    );
+}
+
+#[test]
+fn test_sqlite_is_bundled() {
+    // The comparison is only meaningful against the version the profile was written for
+    assert!(rusqlite::version_number() >= 3_051_000);
+}
+
+/// The Julian day `parser` decodes `input` to, trying the types with the most fields first.
+fn julian_day(parser: &dyn SqliteTemporalParser, input: &str) -> Option<f64> {
+    if let Ok(dt) = parser.parse_offset_date_time(input) {
+        return Some(odt_julian_day(dt));
+    }
+    if let Ok(dt) = parser.parse_primitive_date_time(input) {
+        return Some(pdt_julian_day(dt));
+    }
+    if let Ok(time) = parser.parse_time(input) {
+        return Some(pdt_julian_day(date!(2000 - 01 - 01).with_time(time)));
+    }
+    let date = parser.parse_date(input).ok()?;
+    Some(pdt_julian_day(date.midnight()))
+}
+
+#[test]
+fn test_strategies_agree_with_sqlite() {
+    let connection = Connection::open_in_memory().unwrap();
+    let random = random_inputs();
+
+    let sqlite_profile = Profile::SqliteCompatible;
+    let profiled: [&dyn SqliteTemporalParser; 4] = [
+        &First.with_profile(sqlite_profile),
+        &Second.with_profile(sqlite_profile),
+        &Third.with_profile(sqlite_profile),
+        &Fourth.with_profile(sqlite_profile),
+    ];
+
+    let mut disagreements = Vec::new();
+    for input in EDGE_CASES
+        .iter()
+        .copied()
+        .chain(random.iter().map(String::as_str))
+    {
+        let expected = sqlite(&connection, input).julian_day;
+        for ((name, strategy), profiled) in STRATEGIES.into_iter().zip(profiled) {
+            // The strategies' own languages differ from SQLite's, so they are only compared on
+            // text both accept. Limited to SQLite's profile, a strategy may only accept what
+            // SQLite does, and still decodes it as it would on its own.
+            let own = julian_day(strategy, input);
+            let profiled = julian_day(profiled, input);
+            let agrees = if expected.is_some() {
+                own.is_none_or(|own| Some(own) == expected)
+                    && profiled.is_none_or(|_| profiled == own)
+            } else {
+                profiled.is_none()
+            };
+            if !agrees {
+                disagreements.push(format!(
+                    "{input:?} {name}\n    SQLite:  {expected:?}\n    own:     {own:?}\n    \
+                     profiled: {profiled:?}"
+                ));
+            }
+        }
+    }
+
+    assert!(
+        disagreements.is_empty(),
+        "{} inputs decoded differently from SQLite:\n{}",
+        disagreements.len(),
+        disagreements.join("\n")
+    );
+}
+
+/// A random instant from year 1 to 9998, with an offset SQLite accepts. A third of them are a
+/// whole millisecond away from a rounding boundary, give or take a nanosecond.
+fn random_instant(rng: &mut TestRng) -> OffsetDateTime {
+    let seconds = rng.random_range(-62_135_596_800..253_370_764_800);
+    let nanoseconds = if rng.random_range(0..3) == 0 {
+        let near = [0, 1, 499_999, 500_000, 500_001, 999_999][rng.random_range(0..6)];
+        rng.random_range(0..1000) * 1_000_000 + near
+    } else {
+        rng.random_range(0..1_000_000_000)
+    };
+    let offset_minutes = rng.random_range(-(14 * 60 + 59)..=14 * 60 + 59);
+    let offset = UtcOffset::from_whole_seconds(offset_minutes * 60).unwrap();
+    (OffsetDateTime::UNIX_EPOCH + Duration::new(seconds, nanoseconds)).to_offset(offset)
+}
+
+#[test]
+fn test_encoders_agree_with_sqlite() {
+    let connection = Connection::open_in_memory().unwrap();
+    let mut rng = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+    let numbers = |text: &str| -> (f64, i64) {
+        connection
+            .query_row("SELECT julianday(?1), unixepoch(?1)", [text], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap()
+    };
+
+    let mut disagreements = Vec::new();
+    for _ in 0..20_000 {
+        let odt = random_instant(&mut rng);
+        let pdt = PrimitiveDateTime::new(odt.date(), odt.time());
+        // Nine digits are all SQLite needs to see the value exactly as `time` holds it
+        let pdt_text = pdt.format(NANOSECONDS).unwrap();
+        let odt_text = pdt_text.clone() + &odt.offset().format(OFFSET).unwrap();
+
+        let expected = numbers(&odt_text);
+        let actual = (odt_julian_day(odt), odt_unix_epoch(odt));
+        if actual != expected {
+            disagreements.push(format!(
+                "{odt_text:?}\n    SQLite:  {expected:?}\n    encoded: {actual:?}"
+            ));
+        }
+        let expected = numbers(&pdt_text);
+        let actual = (pdt_julian_day(pdt), pdt_unix_epoch(pdt));
+        if actual != expected {
+            disagreements.push(format!(
+                "{pdt_text:?}\n    SQLite:  {expected:?}\n    encoded: {actual:?}"
+            ));
+        }
+    }
+
+    assert!(
+        disagreements.is_empty(),
+        "{} values encoded differently from SQLite:\n{}",
+        disagreements.len(),
+        disagreements.join("\n")
+    );
+}
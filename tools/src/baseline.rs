@@ -0,0 +1,282 @@
+//! An archive of benchmark runs to check new runs against.
+//!
+//! Timings only compare between runs on the same machine, so each run is saved under the machine
+//! it ran on and the commit it measured. A new run is checked against the latest saved run for its
+//! machine, or the run for a given commit, benchmark by benchmark.
+//!
+//! The archive is JSON with a `version`, so a later change to its layout can tell old archives
+//! apart instead of misreading them.
+
+use crate::estimates::{format_duration, Measurement};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// The layout of the archive this version writes.
+pub const VERSION: u32 = 1;
+
+/// Every saved run, oldest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Archive {
+    pub version: u32,
+    pub runs: Vec<Run>,
+}
+
+/// The results of one `cargo bench` on one machine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Run {
+    pub machine: String,
+    pub commit: String,
+    pub measurements: Vec<Measurement>,
+}
+
+impl Default for Archive {
+    fn default() -> Self {
+        Self {
+            version: VERSION,
+            runs: Vec::new(),
+        }
+    }
+}
+
+impl Archive {
+    /// Reads an archive, refusing one written in a layout this version does not know.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+
+        let Version { version } = serde_json::from_str(json).map_err(|error| error.to_string())?;
+        if version != VERSION {
+            return Err(format!(
+                "archive version {version} is not supported, expected {VERSION}"
+            ));
+        }
+        serde_json::from_str(json).map_err(|error| error.to_string())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap() + "\n"
+    }
+
+    /// Adds `run`, replacing an earlier run of the same commit on the same machine.
+    pub fn save(&mut self, run: Run) {
+        self.runs
+            .retain(|saved| (&saved.machine, &saved.commit) != (&run.machine, &run.commit));
+        self.runs.push(run);
+    }
+
+    /// The run of `commit` on `machine`, or its latest run without a commit.
+    pub fn baseline(&self, machine: &str, commit: Option<&str>) -> Option<&Run> {
+        self.runs
+            .iter()
+            .rev()
+            .filter(|run| run.machine == machine)
+            .find(|run| commit.is_none_or(|commit| run.commit == commit))
+    }
+}
+
+/// How one benchmark changed from the baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub id: String,
+    pub baseline: f64,
+    pub current: f64,
+    /// The change of the mean, as a fraction of the baseline.
+    pub ratio: f64,
+    pub verdict: Verdict,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Regressed,
+    Improved,
+    /// Within the threshold, or within the noise of either run.
+    Unchanged,
+}
+
+/// The benchmarks of a run compared with the baseline's.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub changes: Vec<Change>,
+    /// Benchmarks only in the baseline.
+    pub missing: Vec<String>,
+    /// Benchmarks only in the current run.
+    pub added: Vec<String>,
+}
+
+impl Comparison {
+    pub fn regressions(&self) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(|change| change.verdict == Verdict::Regressed)
+    }
+}
+
+/// Compares the means of `current` with `baseline`. A benchmark regressed when its mean is more
+/// than `threshold`, a fraction, slower and the confidence intervals of the two runs do not
+/// overlap, so noise alone does not fail a check.
+pub fn compare(baseline: &[Measurement], current: &[Measurement], threshold: f64) -> Comparison {
+    let mut baseline: BTreeMap<String, &Measurement> = baseline
+        .iter()
+        .map(|measurement| (measurement.id(), measurement))
+        .collect();
+
+    let mut changes = Vec::new();
+    let mut added = Vec::new();
+    for measurement in current {
+        let id = measurement.id();
+        let Some(before) = baseline.remove(&id) else {
+            added.push(id);
+            continue;
+        };
+        let (before, after) = (before.mean, measurement.mean);
+        let ratio = after.point / before.point - 1.0;
+        let verdict = if ratio > threshold && after.lower > before.upper {
+            Verdict::Regressed
+        } else if ratio < -threshold && after.upper < before.lower {
+            Verdict::Improved
+        } else {
+            Verdict::Unchanged
+        };
+        changes.push(Change {
+            id,
+            baseline: before.point,
+            current: after.point,
+            ratio,
+            verdict,
+        });
+    }
+
+    Comparison {
+        changes,
+        missing: baseline.into_keys().collect(),
+        added,
+    }
+}
+
+/// The comparison as Markdown: a table of every benchmark, then those only in one of the runs.
+pub fn markdown(comparison: &Comparison) -> String {
+    let mut out = String::new();
+    writeln!(out, "| Benchmark | Baseline | Current | Change | |").unwrap();
+    writeln!(out, "|---|---:|---:|---:|---|").unwrap();
+    for change in &comparison.changes {
+        writeln!(
+            out,
+            "| `{}` | {} | {} | {:+.1}% | {} |",
+            change.id.replace('|', "\\|"),
+            format_duration(change.baseline),
+            format_duration(change.current),
+            change.ratio * 100.0,
+            match change.verdict {
+                Verdict::Regressed => "**regressed**",
+                Verdict::Improved => "improved",
+                Verdict::Unchanged => "",
+            },
+        )
+        .unwrap();
+    }
+
+    for (heading, ids) in [
+        ("Not in this run", &comparison.missing),
+        ("Not in the baseline", &comparison.added),
+    ] {
+        if !ids.is_empty() {
+            writeln!(out, "\n{heading}: `{}`", ids.join("`, `")).unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimates::tests::measurement;
+
+    fn run(machine: &str, commit: &str, mean: f64) -> Run {
+        Run {
+            machine: machine.to_owned(),
+            commit: commit.to_owned(),
+            measurements: vec![measurement("Time", "1st ITER", "19:44", mean)],
+        }
+    }
+
+    #[test]
+    fn test_archive_round_trip() {
+        let mut archive = Archive::default();
+        archive.save(run("ci", "abc1234", 100.0));
+        assert_eq!(Archive::parse(&archive.to_json()), Ok(archive));
+
+        assert!(Archive::parse(r#"{"version":2,"runs":[]}"#)
+            .unwrap_err()
+            .contains("version 2"));
+    }
+
+    #[test]
+    fn test_baseline() {
+        let mut archive = Archive::default();
+        archive.save(run("ci", "a", 100.0));
+        archive.save(run("laptop", "a", 50.0));
+        archive.save(run("ci", "b", 110.0));
+        assert_eq!(archive.baseline("ci", None).unwrap().commit, "b");
+        assert_eq!(archive.baseline("ci", Some("a")).unwrap().commit, "a");
+        assert_eq!(archive.baseline("ci", Some("c")), None);
+        assert_eq!(archive.baseline("desktop", None), None);
+
+        // Saving a commit again replaces its run and makes it the latest
+        archive.save(run("ci", "a", 90.0));
+        assert_eq!(archive.runs.len(), 3);
+        let latest = archive.baseline("ci", None).unwrap();
+        assert_eq!(
+            (latest.commit.as_str(), latest.measurements[0].mean.point),
+            ("a", 90.0)
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = [
+            measurement("Time", "1st ITER", "19:44", 100.0),
+            measurement("Time", "2nd ITER", "19:44", 100.0),
+            measurement("Time", "3rd ITER", "19:44", 100.0),
+            measurement("Time", "4th ITER", "19:44", 100.0),
+            measurement("Time", "5th ITER", "19:44", 100.0),
+        ];
+        // Beyond the threshold but within the noise of the baseline
+        let mut noisy = measurement("Time", "4th ITER", "19:44", 111.0);
+        noisy.mean.lower = 95.0;
+        let current = [
+            measurement("Time", "1st ITER", "19:44", 120.0),
+            measurement("Time", "2nd ITER", "19:44", 108.0),
+            measurement("Time", "3rd ITER", "19:44", 80.0),
+            noisy,
+            measurement("Time", "6th ITER", "19:44", 100.0),
+        ];
+        let comparison = compare(&baseline, &current, 0.1);
+
+        let verdicts: Vec<_> = comparison
+            .changes
+            .iter()
+            .map(|change| (change.id.as_str(), change.verdict))
+            .collect();
+        assert_eq!(
+            verdicts,
+            [
+                ("Time/1st ITER/19:44", Verdict::Regressed),
+                ("Time/2nd ITER/19:44", Verdict::Unchanged),
+                ("Time/3rd ITER/19:44", Verdict::Improved),
+                ("Time/4th ITER/19:44", Verdict::Unchanged),
+            ]
+        );
+        assert_eq!(comparison.missing, ["Time/5th ITER/19:44"]);
+        assert_eq!(comparison.added, ["Time/6th ITER/19:44"]);
+        assert_eq!(comparison.regressions().count(), 1);
+
+        let table = markdown(&comparison);
+        assert!(table.contains(
+            "| `Time/1st ITER/19:44` | 100.00 ns | 120.00 ns | +20.0% | **regressed** |"
+        ));
+        assert!(table.contains("Not in this run: `Time/5th ITER/19:44`"));
+    }
+}
@@ -0,0 +1,186 @@
+//! Saves the latest Criterion results to the baseline archive, or checks them against it.
+//!
+//! ```text
+//! cargo run -p time-tests-tools --bin baseline -- save [--criterion DIR] [--archive FILE]
+//!     [--machine NAME] [--commit REV]
+//! cargo run -p time-tests-tools --bin baseline -- check [--criterion DIR] [--archive FILE]
+//!     [--machine NAME] [--against REV] [--threshold PERCENT]
+//! ```
+//!
+//! The archive defaults to `baselines.json`, the machine to `$HOSTNAME` and the commit to the
+//! checked out one. `check` compares with the latest run saved for the machine unless given
+//! `--against`, prints a table of every benchmark and exits with 1 if any regressed by more than
+//! the threshold, 10% by default.
+
+use std::path::PathBuf;
+use std::process::{Command, ExitCode};
+use std::{env, fs, io};
+use time_tests_tools::baseline::{self, Archive, Run};
+use time_tests_tools::estimates::{self, Measurement};
+
+const USAGE: &str = "usage: baseline save [--criterion DIR] [--archive FILE] [--machine NAME] [--commit REV]
+       baseline check [--criterion DIR] [--archive FILE] [--machine NAME] [--against REV] [--threshold PERCENT]";
+
+enum Action {
+    Save {
+        commit: Option<String>,
+    },
+    Check {
+        against: Option<String>,
+        threshold: f64,
+    },
+}
+
+struct Options {
+    action: Action,
+    criterion: PathBuf,
+    archive: PathBuf,
+    machine: Option<String>,
+}
+
+fn options() -> Result<Options, String> {
+    let mut args = env::args().skip(1);
+    let mut options = Options {
+        action: match args.next().as_deref() {
+            Some("save") => Action::Save { commit: None },
+            Some("check") => Action::Check {
+                against: None,
+                threshold: 0.1,
+            },
+            Some(other) => return Err(format!("unknown command `{other}`")),
+            None => return Err("expected a command".to_owned()),
+        },
+        criterion: PathBuf::from("target/criterion"),
+        archive: PathBuf::from("baselines.json"),
+        machine: None,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+        match (arg.as_str(), &mut options.action) {
+            ("--criterion", _) => options.criterion = value()?.into(),
+            ("--archive", _) => options.archive = value()?.into(),
+            ("--machine", _) => options.machine = Some(value()?),
+            ("--commit", Action::Save { commit }) => *commit = Some(value()?),
+            ("--against", Action::Check { against, .. }) => *against = Some(value()?),
+            ("--threshold", Action::Check { threshold, .. }) => {
+                let percent = value()?;
+                *threshold = percent
+                    .trim_end_matches('%')
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|percent| percent.is_finite() && *percent >= 0.0)
+                    .ok_or_else(|| format!("bad threshold `{percent}`"))?
+                    / 100.0;
+            }
+            (other, _) => return Err(format!("unknown argument `{other}`")),
+        }
+    }
+    Ok(options)
+}
+
+fn machine(options: &Options) -> Result<String, String> {
+    options
+        .machine
+        .clone()
+        .or_else(|| env::var("HOSTNAME").ok())
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|machine| machine.trim().to_owned())
+        .filter(|machine| !machine.is_empty())
+        .ok_or_else(|| "cannot tell which machine this is; pass --machine".to_owned())
+}
+
+/// The abbreviated hash of the checked out commit.
+fn head() -> Result<String, String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .ok_or("cannot find the checked out commit; pass --commit")?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+fn load_archive(options: &Options) -> Result<Archive, String> {
+    match fs::read_to_string(&options.archive) {
+        Ok(json) => Archive::parse(&json),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Archive::default()),
+        Err(error) => Err(error.to_string()),
+    }
+    .map_err(|error| format!("{}: {error}", options.archive.display()))
+}
+
+fn load_measurements(options: &Options) -> Result<Vec<Measurement>, String> {
+    let measurements = estimates::load(&options.criterion).map_err(|error| error.to_string())?;
+    if measurements.is_empty() {
+        return Err(format!(
+            "no benchmark results in {}; run `cargo bench` first",
+            options.criterion.display()
+        ));
+    }
+    Ok(measurements)
+}
+
+/// Whether the run passed: saving always does, checking does if nothing regressed.
+fn run(options: Options) -> Result<bool, String> {
+    let machine = machine(&options)?;
+    let mut archive = load_archive(&options)?;
+    let measurements = load_measurements(&options)?;
+
+    match &options.action {
+        Action::Save { commit } => {
+            let commit = commit.clone().map_or_else(head, Ok)?;
+            println!(
+                "saving {} benchmarks as {commit} on {machine}",
+                measurements.len()
+            );
+            archive.save(Run {
+                machine,
+                commit,
+                measurements,
+            });
+            fs::write(&options.archive, archive.to_json())
+                .map_err(|error| format!("{}: {error}", options.archive.display()))?;
+            Ok(true)
+        }
+        Action::Check { against, threshold } => {
+            let Some(baseline) = archive.baseline(&machine, against.as_deref()) else {
+                return Err(match against {
+                    Some(commit) => format!("no run of {commit} on {machine} is saved"),
+                    None => format!("no run on {machine} is saved; run `baseline save` first"),
+                });
+            };
+            let comparison = baseline::compare(&baseline.measurements, &measurements, *threshold);
+            println!(
+                "Compared with {} on {machine}, failing on regressions over {}%\n",
+                baseline.commit,
+                threshold * 100.0
+            );
+            print!("{}", baseline::markdown(&comparison));
+
+            let regressions = comparison.regressions().count();
+            if regressions > 0 {
+                eprintln!("\nbaseline: {regressions} benchmarks regressed");
+            }
+            Ok(regressions == 0)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match options() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(options) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("baseline: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Ranks the functions of each benchmark group for a workload and recommends one, following the
+//! policy in `time_tests_tools::policy`.
+//!
+//! ```text
+//! cargo run -p time-tests-tools --bin decide -- [--criterion DIR] [--workload FILE]
+//! ```
+//!
+//! Without a workload every input is weighted the same.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::{env, fs};
+use time_tests_tools::estimates;
+use time_tests_tools::policy::{self, Workload};
+
+const USAGE: &str = "usage: decide [--criterion DIR] [--workload FILE]";
+
+struct Options {
+    criterion: PathBuf,
+    workload: Option<PathBuf>,
+}
+
+fn options() -> Result<Options, String> {
+    let mut options = Options {
+        criterion: PathBuf::from("target/criterion"),
+        workload: None,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--criterion" => options.criterion = value()?.into(),
+            "--workload" => options.workload = Some(value()?.into()),
+            other => return Err(format!("unknown argument `{other}`")),
+        }
+    }
+    Ok(options)
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let workload = match &options.workload {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| Workload::parse(&text))
+            .map_err(|error| format!("{}: {error}", path.display()))?,
+        None => Workload::default(),
+    };
+    let measurements = estimates::load(&options.criterion).map_err(|error| error.to_string())?;
+    if measurements.is_empty() {
+        return Err(format!(
+            "no benchmark results in {}; run `cargo bench` first",
+            options.criterion.display()
+        ));
+    }
+
+    print!(
+        "{}",
+        policy::markdown(&policy::decide(&measurements, &workload))
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match options() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("decide: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
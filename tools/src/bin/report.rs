@@ -0,0 +1,128 @@
+//! Writes ordered tables and bar charts of the latest Criterion results.
+//!
+//! ```text
+//! cargo run -p time-tests-tools --bin report -- [--criterion DIR] [--out DIR]
+//!     [--by input|function] [--sort name|mean] [--order LABEL,LABEL,...]
+//! ```
+//!
+//! `--order` puts the listed functions, or inputs with `--by function`, first in that order and the
+//! rest after them by name. It replaces `--sort`.
+//!
+//! The output directory gets `README.md`, `index.html` and an SVG chart per group. It defaults to
+//! `summary` inside the Criterion directory, so the site the `Dockerfile` builds serves it as
+//! `/summary/`.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::{env, fs, io};
+use time_tests_tools::estimates;
+use time_tests_tools::report::{self, Grouping, Order};
+
+const USAGE: &str = concat!(
+    "usage: report [--criterion DIR] [--out DIR] [--by input|function] [--sort name|mean] ",
+    "[--order LABEL,LABEL,...]"
+);
+
+struct Options {
+    criterion: PathBuf,
+    out: Option<PathBuf>,
+    grouping: Grouping,
+    order: Order,
+}
+
+fn options() -> Result<Options, String> {
+    let mut options = Options {
+        criterion: PathBuf::from("target/criterion"),
+        out: None,
+        grouping: Grouping::Input,
+        order: Order::Name,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--criterion" => options.criterion = value()?.into(),
+            "--out" => options.out = Some(value()?.into()),
+            "--by" => {
+                options.grouping = match value()?.as_str() {
+                    "input" => Grouping::Input,
+                    "function" => Grouping::Function,
+                    other => return Err(format!("cannot group by `{other}`")),
+                }
+            }
+            "--sort" => {
+                options.order = match value()?.as_str() {
+                    "name" => Order::Name,
+                    "mean" => Order::Mean,
+                    other => return Err(format!("cannot sort by `{other}`")),
+                }
+            }
+            "--order" => {
+                let labels = value()?
+                    .split(',')
+                    .map(|label| label.trim().to_owned())
+                    .collect();
+                options.order = Order::Listed(labels);
+            }
+            other => return Err(format!("unknown argument `{other}`")),
+        }
+    }
+    Ok(options)
+}
+
+fn run(options: Options) -> io::Result<()> {
+    let measurements = estimates::load(&options.criterion)?;
+    if measurements.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "no benchmark results in {}; run `cargo bench` first",
+                options.criterion.display()
+            ),
+        ));
+    }
+
+    let out = options
+        .out
+        .unwrap_or_else(|| options.criterion.join("summary"));
+    fs::create_dir_all(&out)?;
+
+    let groups = report::arrange(&measurements, options.grouping, &options.order);
+    for group in &groups {
+        fs::write(out.join(report::chart_file_name(group)), report::svg(group))?;
+    }
+    fs::write(
+        out.join("README.md"),
+        report::markdown(&groups, options.grouping),
+    )?;
+    fs::write(
+        out.join("index.html"),
+        report::html(&groups, options.grouping),
+    )?;
+
+    println!(
+        "wrote {} benchmarks in {} groups to {}",
+        measurements.len(),
+        groups.len(),
+        out.display()
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match options() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("report: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! Reading the estimates Criterion saves under `target/criterion`.
+//!
+//! Criterion writes a directory per benchmark, named after its group, function and input with
+//! anything unsafe in a path replaced. The latest run of each is in its `new` directory:
+//! `benchmark.json` has the real names and `estimates.json` the statistics, in nanoseconds.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::{fs, io};
+use walkdir::WalkDir;
+
+/// The timing of one benchmark from its latest run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measurement {
+    /// The Criterion group, such as `OffsetDateTime`.
+    pub group: String,
+    /// The function benchmarked, such as `3rd ITER`.
+    pub function: String,
+    /// The input the function was given, if it was benchmarked with one.
+    pub input: Option<String>,
+    pub mean: Estimate,
+    pub median: Estimate,
+}
+
+/// A statistic with its 95% confidence interval, in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Estimate {
+    pub point: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Measurement {
+    /// The id Criterion reports the benchmark under, such as `Time/1st ITER/19:44`.
+    pub fn id(&self) -> String {
+        match &self.input {
+            Some(input) => format!("{}/{}/{input}", self.group, self.function),
+            None => format!("{}/{}", self.group, self.function),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RawBenchmark {
+    group_id: String,
+    function_id: Option<String>,
+    value_str: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawEstimates {
+    mean: RawEstimate,
+    median: RawEstimate,
+}
+
+#[derive(Deserialize)]
+struct RawEstimate {
+    confidence_interval: RawInterval,
+    point_estimate: f64,
+}
+
+#[derive(Deserialize)]
+struct RawInterval {
+    lower_bound: f64,
+    upper_bound: f64,
+}
+
+impl From<RawEstimate> for Estimate {
+    fn from(raw: RawEstimate) -> Self {
+        Self {
+            point: raw.point_estimate,
+            lower: raw.confidence_interval.lower_bound,
+            upper: raw.confidence_interval.upper_bound,
+        }
+    }
+}
+
+/// Reads one benchmark from the contents of its `benchmark.json` and `estimates.json`.
+pub fn parse(benchmark: &str, estimates: &str) -> serde_json::Result<Measurement> {
+    let benchmark: RawBenchmark = serde_json::from_str(benchmark)?;
+    let estimates: RawEstimates = serde_json::from_str(estimates)?;
+
+    // A benchmark identified only by its input, with `BenchmarkId::from_parameter`, is named after it
+    let (function, input) = match benchmark.function_id {
+        Some(function) => (function, benchmark.value_str),
+        None => (benchmark.value_str.unwrap_or_default(), None),
+    };
+
+    Ok(Measurement {
+        group: benchmark.group_id,
+        function,
+        input,
+        mean: estimates.mean.into(),
+        median: estimates.median.into(),
+    })
+}
+
+/// Reads the latest run of every benchmark under `directory`, sorted by id.
+pub fn load(directory: &Path) -> io::Result<Vec<Measurement>> {
+    let mut measurements = Vec::new();
+    for entry in WalkDir::new(directory) {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_name() != "benchmark.json"
+            || path.parent().and_then(Path::file_name) != Some("new".as_ref())
+        {
+            continue;
+        }
+
+        let benchmark = fs::read_to_string(path)?;
+        let estimates = fs::read_to_string(path.with_file_name("estimates.json"))?;
+        let measurement = parse(&benchmark, &estimates).map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {error}", path.display()),
+            )
+        })?;
+        measurements.push(measurement);
+    }
+
+    measurements.sort_by_key(Measurement::id);
+    Ok(measurements)
+}
+
+/// Writes `nanoseconds` with the unit that keeps the number readable, as Criterion does.
+pub fn format_duration(nanoseconds: f64) -> String {
+    if nanoseconds < 1_000.0 {
+        format!("{nanoseconds:.2} ns")
+    } else if nanoseconds < 1_000_000.0 {
+        format!("{:.2} µs", nanoseconds / 1_000.0)
+    } else if nanoseconds < 1_000_000_000.0 {
+        format!("{:.2} ms", nanoseconds / 1_000_000.0)
+    } else {
+        format!("{:.2} s", nanoseconds / 1_000_000_000.0)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// `estimates.json` with the given mean and interval, and a median just below the mean.
+    pub(crate) fn estimates(mean: f64, lower: f64, upper: f64) -> String {
+        let estimate = |point: f64, lower: f64, upper: f64| {
+            format!(
+                r#"{{"confidence_interval":{{"confidence_level":0.95,"lower_bound":{lower},"upper_bound":{upper}}},"point_estimate":{point},"standard_error":1.0}}"#
+            )
+        };
+        format!(
+            r#"{{"mean":{},"median":{},"median_abs_dev":{},"slope":null,"std_dev":{}}}"#,
+            estimate(mean, lower, upper),
+            estimate(mean - 1.0, lower - 1.0, upper - 1.0),
+            estimate(1.0, 0.5, 1.5),
+            estimate(2.0, 1.0, 3.0),
+        )
+    }
+
+    /// A measurement as `parse` reads it from Criterion's files.
+    pub(crate) fn measurement(group: &str, function: &str, input: &str, mean: f64) -> Measurement {
+        let benchmark = format!(
+            r#"{{"group_id":"{group}","function_id":"{function}","value_str":"{input}","throughput":null}}"#
+        );
+        parse(&benchmark, &estimates(mean, mean - 5.0, mean + 5.0)).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let measurement = measurement("Time", "1st ITER", "20:45:31.133", 221.5);
+        assert_eq!(measurement.group, "Time");
+        assert_eq!(measurement.function, "1st ITER");
+        assert_eq!(measurement.input.as_deref(), Some("20:45:31.133"));
+        assert_eq!(
+            measurement.mean,
+            Estimate {
+                point: 221.5,
+                lower: 216.5,
+                upper: 226.5
+            }
+        );
+        assert_eq!(measurement.median.point, 220.5);
+        assert_eq!(measurement.id(), "Time/1st ITER/20:45:31.133");
+    }
+
+    #[test]
+    fn test_parse_without_input() {
+        let benchmark = r#"{"group_id":"Baseline","function_id":"H:M","value_str":null}"#;
+        let measurement = parse(benchmark, &estimates(10.0, 9.0, 11.0)).unwrap();
+        assert_eq!(measurement.function, "H:M");
+        assert_eq!(measurement.input, None);
+        assert_eq!(measurement.id(), "Baseline/H:M");
+
+        let benchmark = r#"{"group_id":"Date","function_id":null,"value_str":"0001-01-01"}"#;
+        let measurement = parse(benchmark, &estimates(10.0, 9.0, 11.0)).unwrap();
+        assert_eq!(measurement.function, "0001-01-01");
+        assert_eq!(measurement.input, None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(221.527), "221.53 ns");
+        assert_eq!(format_duration(1_500.0), "1.50 µs");
+        assert_eq!(format_duration(2_000_000.0), "2.00 ms");
+        assert_eq!(format_duration(3_000_000_000.0), "3.00 s");
+    }
+}
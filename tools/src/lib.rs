@@ -0,0 +1,6 @@
+//! Tools for reading and presenting the results of the `time-tests` benchmarks.
+
+pub mod baseline;
+pub mod estimates;
+pub mod policy;
+pub mod report;
@@ -0,0 +1,341 @@
+//! A written-down policy for choosing between strategies from their benchmark results.
+//!
+//! Each group is decided on its own, over the inputs its functions were benchmarked with. Inputs
+//! are weighted by their shape, the input with every digit replaced by `#`, so a workload can say
+//! how common each format is without caring about the values benchmarked. The policy is:
+//!
+//! 1. Rank the functions by their weighted mean time.
+//! 2. A function whose weighted mean interval overlaps the leader's is tied with it, because the
+//!    benchmarks cannot tell them apart.
+//! 3. Of the tied functions, recommend the one with the best worst case, the slowest of its
+//!    weighted inputs, as the least surprising choice.
+//!
+//! Functions without a result for every weighted input are not ranked.
+
+use crate::estimates::{format_duration, Measurement};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// How common each input shape is, per group.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Workload {
+    weights: BTreeMap<(String, String), f64>,
+}
+
+/// The shape of `input` as workloads write it: `2016-03-07 22:36` is `####-##-## ##:##`.
+pub fn shape(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| if c.is_ascii_digit() { '#' } else { c })
+        .collect()
+}
+
+impl Workload {
+    /// Reads a workload of tab separated `group`, `shape` and `weight` lines, ignoring blank
+    /// lines and `#` comments. Weights are relative to the others in the same group.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut weights = BTreeMap::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line_number = index + 1;
+            let [group, shape, weight] = line.split('\t').collect::<Vec<_>>()[..] else {
+                return Err(format!("line {line_number}: expected 3 fields"));
+            };
+            let weight: f64 = weight
+                .parse()
+                .ok()
+                .filter(|weight: &f64| weight.is_finite() && *weight >= 0.0)
+                .ok_or_else(|| format!("line {line_number}: bad weight `{weight}`"))?;
+            if weights
+                .insert((group.to_owned(), shape.to_owned()), weight)
+                .is_some()
+            {
+                return Err(format!("line {line_number}: `{shape}` is weighted twice"));
+            }
+        }
+        Ok(Self { weights })
+    }
+
+    /// The weight of each input, which shares its shape's weight with the other inputs of the same
+    /// shape. A group the workload does not mention weighs every input the same.
+    fn weigh<'a>(&self, group: &str, inputs: &[&'a str]) -> Vec<(&'a str, f64)> {
+        let weighted = self.weights.keys().any(|(g, _)| g == group);
+        inputs
+            .iter()
+            .map(|&input| {
+                let shape = shape(input);
+                let weight = if weighted {
+                    let same_shape = inputs.iter().filter(|i| self::shape(i) == shape).count();
+                    self.weights
+                        .get(&(group.to_owned(), shape))
+                        .map_or(0.0, |weight| weight / same_shape as f64)
+                } else {
+                    1.0
+                };
+                (input, weight)
+            })
+            .collect()
+    }
+}
+
+/// One function's results over a group's weighted inputs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    pub function: String,
+    pub weighted_mean: f64,
+    /// The weighted means of the lower and upper bounds of each input's interval.
+    pub interval: (f64, f64),
+    pub geometric_mean: f64,
+    pub worst_case: f64,
+    pub worst_input: String,
+    /// Whether the benchmarks cannot tell this function apart from the fastest.
+    pub tied: bool,
+}
+
+/// The ranking of one group's functions and the function the policy recommends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    pub group: String,
+    pub ranking: Vec<Score>,
+    pub recommended: Option<String>,
+    /// Inputs given no weight by the workload.
+    pub ignored_inputs: Vec<String>,
+    /// Functions missing a result for a weighted input.
+    pub incomplete: Vec<String>,
+}
+
+/// Applies the policy to each group of `measurements` that has inputs.
+pub fn decide(measurements: &[Measurement], workload: &Workload) -> Vec<Decision> {
+    let mut groups: BTreeMap<&str, BTreeMap<&str, BTreeMap<&str, &Measurement>>> = BTreeMap::new();
+    for measurement in measurements {
+        if let Some(input) = &measurement.input {
+            groups
+                .entry(&measurement.group)
+                .or_default()
+                .entry(&measurement.function)
+                .or_default()
+                .insert(input, measurement);
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(group, functions)| {
+            let mut inputs: Vec<&str> =
+                functions.values().flat_map(|m| m.keys().copied()).collect();
+            inputs.sort_unstable();
+            inputs.dedup();
+            let weights = workload.weigh(group, &inputs);
+            let (weights, ignored): (Vec<_>, Vec<_>) =
+                weights.into_iter().partition(|&(_, weight)| weight > 0.0);
+
+            let mut ranking = Vec::new();
+            let mut incomplete = Vec::new();
+            for (function, results) in functions {
+                match score(function, &results, &weights) {
+                    Some(score) => ranking.push(score),
+                    None => incomplete.push(function.to_owned()),
+                }
+            }
+            ranking.sort_by(|a, b| a.weighted_mean.total_cmp(&b.weighted_mean));
+
+            let leader = ranking.first().map(|score| score.interval);
+            for score in &mut ranking {
+                score.tied = leader.is_some_and(|(lower, upper)| {
+                    score.interval.0 <= upper && lower <= score.interval.1
+                });
+            }
+            let recommended = ranking
+                .iter()
+                .filter(|score| score.tied)
+                .min_by(|a, b| a.worst_case.total_cmp(&b.worst_case))
+                .map(|score| score.function.clone());
+
+            Decision {
+                group: group.to_owned(),
+                ranking,
+                recommended,
+                ignored_inputs: ignored.into_iter().map(|(i, _)| i.to_owned()).collect(),
+                incomplete,
+            }
+        })
+        .collect()
+}
+
+fn score(
+    function: &str,
+    results: &BTreeMap<&str, &Measurement>,
+    weights: &[(&str, f64)],
+) -> Option<Score> {
+    if weights.is_empty() {
+        return None;
+    }
+    let mut total = 0.0;
+    let (mut mean, mut lower, mut upper, mut log) = (0.0, 0.0, 0.0, 0.0);
+    let mut worst: Option<(&str, f64)> = None;
+    for &(input, weight) in weights {
+        let estimate = results.get(input)?.mean;
+        total += weight;
+        mean += weight * estimate.point;
+        lower += weight * estimate.lower;
+        upper += weight * estimate.upper;
+        log += weight * estimate.point.ln();
+        if worst.is_none_or(|(_, worst)| estimate.point > worst) {
+            worst = Some((input, estimate.point));
+        }
+    }
+    let (worst_input, worst_case) = worst?;
+
+    Some(Score {
+        function: function.to_owned(),
+        weighted_mean: mean / total,
+        interval: (lower / total, upper / total),
+        geometric_mean: (log / total).exp(),
+        worst_case,
+        worst_input: worst_input.to_owned(),
+        tied: false,
+    })
+}
+
+/// The decisions as Markdown, a ranking table and a recommendation per group.
+pub fn markdown(decisions: &[Decision]) -> String {
+    let mut out = String::new();
+    for decision in decisions {
+        writeln!(out, "## {}\n", decision.group).unwrap();
+        writeln!(
+            out,
+            "| Rank | Function | Weighted mean | 95% CI | Geometric mean | Worst case | Tied |"
+        )
+        .unwrap();
+        writeln!(out, "|---:|---|---:|---:|---:|---|---|").unwrap();
+        for (rank, score) in decision.ranking.iter().enumerate() {
+            writeln!(
+                out,
+                "| {} | `{}` | {} | {} – {} | {} | {} (`{}`) | {} |",
+                rank + 1,
+                score.function,
+                format_duration(score.weighted_mean),
+                format_duration(score.interval.0),
+                format_duration(score.interval.1),
+                format_duration(score.geometric_mean),
+                format_duration(score.worst_case),
+                score.worst_input.replace('|', "\\|"),
+                if score.tied { "yes" } else { "" },
+            )
+            .unwrap();
+        }
+        out.push('\n');
+
+        match &decision.recommended {
+            Some(function) => writeln!(out, "Recommended: `{function}`").unwrap(),
+            None => writeln!(out, "No recommendation: nothing could be ranked").unwrap(),
+        }
+        if !decision.incomplete.is_empty() {
+            writeln!(
+                out,
+                "\nNot ranked, missing weighted inputs: `{}`",
+                decision.incomplete.join("`, `")
+            )
+            .unwrap();
+        }
+        if !decision.ignored_inputs.is_empty() {
+            writeln!(
+                out,
+                "\nNot weighted by the workload: `{}`",
+                decision.ignored_inputs.join("`, `")
+            )
+            .unwrap();
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimates::tests::measurement;
+
+    #[test]
+    fn test_shape() {
+        assert_eq!(
+            shape("2016-03-07T22:36:55.135+03:30"),
+            "####-##-##T##:##:##.###+##:##"
+        );
+        assert_eq!(shape("19:44"), "##:##");
+    }
+
+    #[test]
+    fn test_parse_workload() {
+        let workload = Workload::parse("# comment\n\nTime\t##:##\t9\nTime\t##:##:##\t1\n").unwrap();
+        assert_eq!(
+            workload.weigh("Time", &["19:44", "21:46", "21:46:32"]),
+            [("19:44", 4.5), ("21:46", 4.5), ("21:46:32", 1.0)]
+        );
+        assert_eq!(
+            workload.weigh("Date", &["2016-03-07"]),
+            [("2016-03-07", 1.0)]
+        );
+
+        assert!(Workload::parse("Time\t##:##").is_err());
+        assert!(Workload::parse("Time\t##:##\t-1").is_err());
+        assert!(Workload::parse("Time\t##:##\t1\nTime\t##:##\t2").is_err());
+    }
+
+    #[test]
+    fn test_decide() {
+        // `3rd` is fastest on the common input but slow on the rare one, and `4th` is within
+        // the interval of `3rd` with a better worst case
+        let measurements = [
+            measurement("Time", "1st ITER", "19:44", 100.0),
+            measurement("Time", "1st ITER", "21:46:32", 100.0),
+            measurement("Time", "3rd ITER", "19:44", 10.0),
+            measurement("Time", "3rd ITER", "21:46:32", 50.0),
+            measurement("Time", "4th ITER", "19:44", 16.0),
+            measurement("Time", "4th ITER", "21:46:32", 20.0),
+            measurement("Time", "5th ITER", "19:44", 1.0),
+        ];
+        let workload = Workload::parse("Time\t##:##\t9\nTime\t##:##:##\t1").unwrap();
+        let decisions = decide(&measurements, &workload);
+
+        assert_eq!(decisions.len(), 1);
+        let decision = &decisions[0];
+        let ranking: Vec<_> = decision
+            .ranking
+            .iter()
+            .map(|score| (score.function.as_str(), score.weighted_mean, score.tied))
+            .collect();
+        assert_eq!(
+            ranking,
+            [
+                ("3rd ITER", 14.0, true),
+                ("4th ITER", 16.4, true),
+                ("1st ITER", 100.0, false)
+            ]
+        );
+        assert_eq!(decision.recommended.as_deref(), Some("4th ITER"));
+        assert_eq!(decision.incomplete, ["5th ITER"]);
+        assert!(decision.ignored_inputs.is_empty());
+
+        let third = &decision.ranking[0];
+        assert_eq!(
+            (third.worst_case, third.worst_input.as_str()),
+            (50.0, "21:46:32")
+        );
+        assert!((third.geometric_mean - (10f64.ln() * 0.9 + 50f64.ln() * 0.1).exp()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unweighted_inputs_are_reported() {
+        let measurements = [
+            measurement("Time", "1st ITER", "19:44", 10.0),
+            measurement("Time", "1st ITER", "21:46:32", 20.0),
+        ];
+        let workload = Workload::parse("Time\t##:##\t1").unwrap();
+        let decisions = decide(&measurements, &workload);
+        assert_eq!(decisions[0].ignored_inputs, ["21:46:32"]);
+        assert_eq!(decisions[0].ranking[0].weighted_mean, 10.0);
+    }
+}
@@ -0,0 +1,426 @@
+//! Ordered tables and bar charts of benchmark results, for the README or the static site.
+//!
+//! Criterion's own report orders the functions in its violin plots as it likes. Here each group is
+//! split into sections, one per input or one per function, and the rows of each section are put in
+//! the order asked for, which can be a list of the labels.
+
+use crate::estimates::{format_duration, Measurement};
+use std::fmt::Write;
+
+/// What each section of a group holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    /// Every function's result for one input.
+    Input,
+    /// One function's result for every input.
+    Function,
+}
+
+/// How the rows of each section are ordered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Order {
+    Name,
+    /// Fastest first.
+    Mean,
+    /// The labels in this order, then any others in name order.
+    Listed(Vec<String>),
+}
+
+/// The results of one Criterion group.
+#[derive(Debug)]
+pub struct Group<'a> {
+    pub name: &'a str,
+    pub sections: Vec<Section<'a>>,
+}
+
+/// The results for one input or one function, or for every benchmark in a group that has no
+/// inputs.
+#[derive(Debug)]
+pub struct Section<'a> {
+    pub heading: Option<&'a str>,
+    pub rows: Vec<Row<'a>>,
+}
+
+#[derive(Debug)]
+pub struct Row<'a> {
+    pub label: &'a str,
+    pub measurement: &'a Measurement,
+}
+
+impl Section<'_> {
+    fn fastest(&self) -> f64 {
+        self.rows
+            .iter()
+            .map(|row| row.measurement.mean.point)
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+/// Splits `measurements` into groups and sections, with the groups and sections in name order and
+/// the rows in `order`.
+pub fn arrange<'a>(
+    measurements: &'a [Measurement],
+    grouping: Grouping,
+    order: &Order,
+) -> Vec<Group<'a>> {
+    let mut groups: Vec<Group<'_>> = Vec::new();
+    for measurement in measurements {
+        let (heading, label) = match (grouping, &measurement.input) {
+            (Grouping::Input, Some(input)) => (Some(input.as_str()), &measurement.function),
+            (Grouping::Function, Some(input)) => (Some(measurement.function.as_str()), input),
+            (_, None) => (None, &measurement.function),
+        };
+
+        let group = match groups.iter_mut().position(|g| g.name == measurement.group) {
+            Some(index) => &mut groups[index],
+            None => {
+                groups.push(Group {
+                    name: &measurement.group,
+                    sections: Vec::new(),
+                });
+                groups.last_mut().unwrap()
+            }
+        };
+        let section = match group.sections.iter().position(|s| s.heading == heading) {
+            Some(index) => &mut group.sections[index],
+            None => {
+                group.sections.push(Section {
+                    heading,
+                    rows: Vec::new(),
+                });
+                group.sections.last_mut().unwrap()
+            }
+        };
+        section.rows.push(Row { label, measurement });
+    }
+
+    groups.sort_by_key(|group| group.name);
+    for group in &mut groups {
+        group.sections.sort_by_key(|section| section.heading);
+        for section in &mut group.sections {
+            match order {
+                Order::Name => section.rows.sort_by_key(|row| row.label),
+                Order::Mean => section.rows.sort_by(|a, b| {
+                    a.measurement
+                        .mean
+                        .point
+                        .total_cmp(&b.measurement.mean.point)
+                }),
+                Order::Listed(labels) => section.rows.sort_by_key(|row| {
+                    let position = labels.iter().position(|label| label == row.label);
+                    (position.unwrap_or(labels.len()), row.label)
+                }),
+            }
+        }
+    }
+    groups
+}
+
+/// The name of the chart file for `group`.
+pub fn chart_file_name(group: &Group<'_>) -> String {
+    let slug: String = group
+        .name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{slug}.svg")
+}
+
+fn label_heading(grouping: Grouping) -> &'static str {
+    match grouping {
+        Grouping::Input => "Function",
+        Grouping::Function => "Input",
+    }
+}
+
+/// The cells of a row: the label, the mean, its interval, the median and the mean relative to
+/// the fastest row of the section.
+fn cells(row: &Row<'_>, fastest: f64) -> [String; 5] {
+    let measurement = row.measurement;
+    [
+        row.label.to_owned(),
+        format_duration(measurement.mean.point),
+        format!(
+            "{} – {}",
+            format_duration(measurement.mean.lower),
+            format_duration(measurement.mean.upper)
+        ),
+        format_duration(measurement.median.point),
+        format!("×{:.2}", measurement.mean.point / fastest),
+    ]
+}
+
+const COLUMNS: [&str; 4] = ["Mean", "95% CI", "Median", "vs fastest"];
+
+/// A Markdown document with each group's chart and tables, linking the charts by file name.
+pub fn markdown(groups: &[Group<'_>], grouping: Grouping) -> String {
+    let mut out = String::new();
+    for group in groups {
+        writeln!(out, "## {}\n", group.name).unwrap();
+        writeln!(out, "![{}]({})\n", group.name, chart_file_name(group)).unwrap();
+        for section in &group.sections {
+            if let Some(heading) = section.heading {
+                writeln!(out, "### `{heading}`\n").unwrap();
+            }
+            writeln!(
+                out,
+                "| {} | {} |",
+                label_heading(grouping),
+                COLUMNS.join(" | ")
+            )
+            .unwrap();
+            writeln!(out, "|---|---:|---:|---:|---:|").unwrap();
+            let fastest = section.fastest();
+            for row in &section.rows {
+                let [label, rest @ ..] = cells(row, fastest);
+                // Inputs can contain `|`, which would end the cell
+                let label = label.replace('|', "\\|");
+                writeln!(out, "| `{label}` | {} |", rest.join(" | ")).unwrap();
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A standalone HTML page with each group's chart and tables.
+pub fn html(groups: &[Group<'_>], grouping: Grouping) -> String {
+    let mut out = String::from(concat!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n",
+        "<title>Benchmark report</title>\n<style>\n",
+        "body { font-family: sans-serif; margin: 2em; }\n",
+        "table { border-collapse: collapse; margin-bottom: 1.5em; }\n",
+        "th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; }\n",
+        "td { text-align: right; } td:first-child { text-align: left; font-family: monospace; }\n",
+        "</style>\n</head>\n<body>\n<h1>Benchmark report</h1>\n",
+    ));
+    for group in groups {
+        let name = escape(group.name);
+        writeln!(out, "<h2>{name}</h2>").unwrap();
+        writeln!(
+            out,
+            "<img src=\"{}\" alt=\"{name}\">",
+            chart_file_name(group)
+        )
+        .unwrap();
+        for section in &group.sections {
+            if let Some(heading) = section.heading {
+                writeln!(out, "<h3><code>{}</code></h3>", escape(heading)).unwrap();
+            }
+            writeln!(out, "<table>").unwrap();
+            write!(out, "<tr><th>{}</th>", label_heading(grouping)).unwrap();
+            for column in COLUMNS {
+                write!(out, "<th>{column}</th>").unwrap();
+            }
+            writeln!(out, "</tr>").unwrap();
+            let fastest = section.fastest();
+            for row in &section.rows {
+                write!(out, "<tr>").unwrap();
+                for cell in cells(row, fastest) {
+                    write!(out, "<td>{}</td>", escape(&cell)).unwrap();
+                }
+                writeln!(out, "</tr>").unwrap();
+            }
+            writeln!(out, "</table>").unwrap();
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+const LABEL_WIDTH: f64 = 260.0;
+const BAR_WIDTH: f64 = 440.0;
+const VALUE_WIDTH: f64 = 100.0;
+const ROW_HEIGHT: f64 = 18.0;
+const HEADING_HEIGHT: f64 = 24.0;
+
+/// A horizontal bar chart of the group's means, with a line across each bar for its interval. The
+/// bars are in the same order as the tables.
+pub fn svg(group: &Group<'_>) -> String {
+    let scale = group
+        .sections
+        .iter()
+        .flat_map(|section| &section.rows)
+        .map(|row| row.measurement.mean.upper)
+        .fold(0.0, f64::max);
+    let scale = BAR_WIDTH / if scale > 0.0 { scale } else { 1.0 };
+
+    let mut body = String::new();
+    let mut y = HEADING_HEIGHT;
+    writeln!(
+        body,
+        "<text x=\"0\" y=\"{}\" font-weight=\"bold\" font-size=\"16\">{}</text>",
+        y - 6.0,
+        escape(group.name)
+    )
+    .unwrap();
+    for section in &group.sections {
+        if let Some(heading) = section.heading {
+            y += HEADING_HEIGHT;
+            writeln!(
+                body,
+                "<text x=\"0\" y=\"{}\" font-weight=\"bold\">{}</text>",
+                y - 6.0,
+                escape(heading)
+            )
+            .unwrap();
+        }
+        for row in &section.rows {
+            let mean = row.measurement.mean;
+            let middle = y + ROW_HEIGHT / 2.0;
+            writeln!(
+                body,
+                concat!(
+                    "<text x=\"{label_x}\" y=\"{text_y}\" text-anchor=\"end\">{label}</text>",
+                    "<rect x=\"{bar_x}\" y=\"{bar_y}\" width=\"{width:.1}\" height=\"{height}\" fill=\"#4c72b0\"/>",
+                    "<line x1=\"{lower:.1}\" x2=\"{upper:.1}\" y1=\"{middle}\" y2=\"{middle}\" stroke=\"#222\"/>",
+                    "<text x=\"{value_x}\" y=\"{text_y}\">{value}</text>",
+                ),
+                label_x = LABEL_WIDTH - 6.0,
+                text_y = y + ROW_HEIGHT - 5.0,
+                label = escape(row.label),
+                bar_x = LABEL_WIDTH,
+                bar_y = y + 2.0,
+                width = mean.point * scale,
+                height = ROW_HEIGHT - 4.0,
+                lower = LABEL_WIDTH + mean.lower * scale,
+                upper = LABEL_WIDTH + mean.upper * scale,
+                middle = middle,
+                value_x = LABEL_WIDTH + BAR_WIDTH + 6.0,
+                value = format_duration(mean.point),
+            )
+            .unwrap();
+            y += ROW_HEIGHT;
+        }
+    }
+
+    format!(
+        concat!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" ",
+            "font-family=\"sans-serif\" font-size=\"12\">\n",
+            "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n{body}</svg>\n",
+        ),
+        width = LABEL_WIDTH + BAR_WIDTH + VALUE_WIDTH,
+        height = y + 6.0,
+        body = body,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimates::tests::measurement;
+
+    fn measurements() -> Vec<Measurement> {
+        vec![
+            measurement("Time", "1st ITER", "19:44", 30.0),
+            measurement("Time", "1st ITER", "21:46:32", 20.0),
+            measurement("Time", "3rd ITER", "19:44", 10.0),
+            measurement("Time", "3rd ITER", "21:46:32", 40.0),
+            measurement("Date", "1st ITER", "2016-03-07", 5.0),
+        ]
+    }
+
+    fn layout<'a>(groups: &[Group<'a>]) -> Vec<(&'a str, Option<&'a str>, Vec<&'a str>)> {
+        groups
+            .iter()
+            .flat_map(|group| {
+                group.sections.iter().map(|section| {
+                    let labels = section.rows.iter().map(|row| row.label).collect();
+                    (group.name, section.heading, labels)
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_arrange_by_input() {
+        let measurements = measurements();
+        assert_eq!(
+            layout(&arrange(&measurements, Grouping::Input, &Order::Name)),
+            [
+                ("Date", Some("2016-03-07"), vec!["1st ITER"]),
+                ("Time", Some("19:44"), vec!["1st ITER", "3rd ITER"]),
+                ("Time", Some("21:46:32"), vec!["1st ITER", "3rd ITER"]),
+            ]
+        );
+        assert_eq!(
+            layout(&arrange(&measurements, Grouping::Input, &Order::Mean)),
+            [
+                ("Date", Some("2016-03-07"), vec!["1st ITER"]),
+                ("Time", Some("19:44"), vec!["3rd ITER", "1st ITER"]),
+                ("Time", Some("21:46:32"), vec!["1st ITER", "3rd ITER"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arrange_by_function() {
+        let measurements = measurements();
+        assert_eq!(
+            layout(&arrange(&measurements, Grouping::Function, &Order::Mean)),
+            [
+                ("Date", Some("1st ITER"), vec!["2016-03-07"]),
+                ("Time", Some("1st ITER"), vec!["21:46:32", "19:44"]),
+                ("Time", Some("3rd ITER"), vec!["19:44", "21:46:32"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arrange_in_listed_order() {
+        let mut measurements = measurements();
+        measurements.push(measurement("Time", "2nd ITER", "19:44", 15.0));
+        measurements.push(measurement("Time", "4th ITER", "19:44", 25.0));
+        let order = Order::Listed(vec!["4th ITER".to_owned(), "1st ITER".to_owned()]);
+        assert_eq!(
+            layout(&arrange(&measurements, Grouping::Input, &order)),
+            [
+                ("Date", Some("2016-03-07"), vec!["1st ITER"]),
+                (
+                    "Time",
+                    Some("19:44"),
+                    vec!["4th ITER", "1st ITER", "2nd ITER", "3rd ITER"]
+                ),
+                ("Time", Some("21:46:32"), vec!["1st ITER", "3rd ITER"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        let measurements = measurements();
+        let groups = arrange(&measurements[..2], Grouping::Function, &Order::Mean);
+        assert_eq!(
+            markdown(&groups, Grouping::Function),
+            "## Time\n\n![Time](time.svg)\n\n### `1st ITER`\n\n\
+             | Input | Mean | 95% CI | Median | vs fastest |\n\
+             |---|---:|---:|---:|---:|\n\
+             | `21:46:32` | 20.00 ns | 15.00 ns – 25.00 ns | 19.00 ns | ×1.00 |\n\
+             | `19:44` | 30.00 ns | 25.00 ns – 35.00 ns | 29.00 ns | ×1.50 |\n\n"
+        );
+    }
+
+    #[test]
+    fn test_escaping() {
+        let measurements = [measurement("Encode", "write!", "<a|b>", 1.0)];
+        let groups = arrange(&measurements, Grouping::Function, &Order::Name);
+        assert!(markdown(&groups, Grouping::Function).contains("| `<a\\|b>` |"));
+        assert!(html(&groups, Grouping::Function).contains("<td>&lt;a|b&gt;</td>"));
+        assert!(svg(&groups[0]).contains(">&lt;a|b&gt;</text>"));
+        assert_eq!(chart_file_name(&groups[0]), "encode.svg");
+    }
+}